@@ -1,80 +1,164 @@
-use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::sync::OnceLock;
 
 advent_of_code::solution!(1);
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let mut sum = 0;
-    for line in input.split('\n') {
-        let digits = line
-            .chars()
-            .filter(|c| c.is_ascii_digit())
-            .collect::<Vec<char>>();
-
-        let c1 = digits.first();
-        let c2 = digits.last();
-
-        if c1.is_none() {
-            continue;
-        }
+const DIGITS: [(&str, u32); 9] = [
+    ("1", 1),
+    ("2", 2),
+    ("3", 3),
+    ("4", 4),
+    ("5", 5),
+    ("6", 6),
+    ("7", 7),
+    ("8", 8),
+    ("9", 9),
+];
+
+const DIGIT_WORDS: [(&str, u32); 9] = [
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+#[derive(Debug, Default)]
+struct Node {
+    children: Vec<(u8, usize)>,
+    /// The longest node that is a proper suffix of this one.
+    fail: usize,
+    /// The value of the longest word ending here, including through `fail`.
+    value: Option<u32>,
+}
 
-        let s = format!("{}{}", c1?, c2?);
+/// An Aho–Corasick automaton over bytes, matching every word in a single pass over a line.
+#[derive(Debug)]
+struct Automaton {
+    nodes: Vec<Node>,
+}
 
-        let n = s.parse::<u32>();
+impl Automaton {
+    fn new<'a>(words: impl IntoIterator<Item = (impl IntoIterator<Item = &'a u8>, u32)>) -> Self {
+        let mut nodes = vec![Node::default()];
+        for (word, value) in words {
+            let mut current = 0;
+            for &byte in word {
+                current = match nodes[current].children.iter().find(|&&(b, _)| b == byte) {
+                    Some(&(_, child)) => child,
+                    None => {
+                        nodes.push(Node::default());
+                        let child = nodes.len() - 1;
+                        nodes[current].children.push((byte, child));
+                        child
+                    }
+                };
+            }
+            nodes[current].value = Some(value);
+        }
 
-        sum += n.unwrap();
+        // Breadth first, so the failure links of shorter nodes are known before they are needed.
+        let mut automaton = Automaton { nodes };
+        let mut queue = VecDeque::from([0]);
+        while let Some(current) = queue.pop_front() {
+            for (byte, child) in automaton.nodes[current].children.clone() {
+                let fail = match current {
+                    0 => 0,
+                    _ => automaton.next(automaton.nodes[current].fail, byte),
+                };
+                let inherited = automaton.nodes[fail].value;
+                let node = &mut automaton.nodes[child];
+                node.fail = fail;
+                node.value = node.value.or(inherited);
+                queue.push_back(child);
+            }
+        }
+        automaton
     }
 
-    Some(sum)
-}
+    /// The node reached from `current` by reading `byte`.
+    fn next(&self, mut current: usize, byte: u8) -> usize {
+        loop {
+            let node = &self.nodes[current];
+            if let Some(&(_, child)) = node.children.iter().find(|&&(b, _)| b == byte) {
+                return child;
+            }
+            if current == 0 {
+                return 0;
+            }
+            current = node.fail;
+        }
+    }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let matchers = HashSet::from([
-        ("1", 1),
-        ("2", 2),
-        ("3", 3),
-        ("4", 4),
-        ("5", 5),
-        ("6", 6),
-        ("7", 7),
-        ("8", 8),
-        ("9", 9),
-        ("one", 1),
-        ("two", 2),
-        ("three", 3),
-        ("four", 4),
-        ("five", 5),
-        ("six", 6),
-        ("seven", 7),
-        ("eight", 8),
-        ("nine", 9),
-    ]);
-    let mut sum = 0;
-    for line in input.split('\n') {
-        let mut possible_digits = Vec::from_iter((0..line.len()).map(|_| None));
-        for i in 0..line.len() {
-            for (s, val) in matchers.iter() {
-                if line[i..].starts_with(s) {
-                    possible_digits[i] = Some(*val);
-                    break;
-                }
+    /// The value of the first word to end in `bytes`, if any.
+    fn first_match<'a>(&self, bytes: impl IntoIterator<Item = &'a u8>) -> Option<u32> {
+        let mut current = 0;
+        for &byte in bytes {
+            current = self.next(current, byte);
+            if let Some(value) = self.nodes[current].value {
+                return Some(value);
             }
         }
+        None
+    }
+}
 
-        let digits = possible_digits.iter().flatten().collect::<Vec<&u32>>();
-        let c1 = digits.first();
-        let c2 = digits.last();
+/// Finds the first and last digit of a line, with one automaton per reading direction.
+///
+/// Both ends are scanned independently, so words sharing letters like `eightwo` count as both
+/// `8` at the start and `2` at the end.
+struct Scanner {
+    forward: Automaton,
+    backward: Automaton,
+}
 
-        if c1.is_none() {
-            continue;
+impl Scanner {
+    fn new(words: &[(&str, u32)]) -> Self {
+        Scanner {
+            forward: Automaton::new(words.iter().map(|&(w, v)| (w.as_bytes(), v))),
+            backward: Automaton::new(words.iter().map(|&(w, v)| (w.as_bytes().iter().rev(), v))),
         }
-        let s = format!("{}{}", c1?, c2?);
+    }
 
-        let n = s.parse::<u32>();
+    fn calibration_value(&self, line: &str) -> Option<u32> {
+        let bytes = line.as_bytes();
+        let first = self.forward.first_match(bytes)?;
+        let last = self.backward.first_match(bytes.iter().rev())?;
+        Some(first * 10 + last)
+    }
 
-        sum += n.unwrap();
+    /// Sum of the calibration values of all lines that contain a digit.
+    fn sum(&self, input: &str) -> u32 {
+        input
+            .lines()
+            .filter_map(|line| self.calibration_value(line))
+            .sum()
     }
+}
+
+fn digits() -> &'static Scanner {
+    static SCANNER: OnceLock<Scanner> = OnceLock::new();
+    SCANNER.get_or_init(|| Scanner::new(&DIGITS))
+}
 
-    Some(sum)
+fn digits_and_words() -> &'static Scanner {
+    static SCANNER: OnceLock<Scanner> = OnceLock::new();
+    SCANNER.get_or_init(|| {
+        let words: Vec<_> = DIGITS.into_iter().chain(DIGIT_WORDS).collect();
+        Scanner::new(&words)
+    })
+}
+
+pub fn part_one(input: &str) -> Option<u32> {
+    Some(digits().sum(input))
+}
+
+pub fn part_two(input: &str) -> Option<u32> {
+    Some(digits_and_words().sum(input))
 }
 
 #[cfg(test)]
@@ -96,7 +180,28 @@ xtwone3four
 4nineeightseven2
 zoneight234
 7pqrstsixteen";
-        let result = part_two(&input);
+        let result = part_two(input);
         assert_eq!(result, Some(281));
     }
+
+    #[test]
+    fn test_overlapping_words() {
+        let scanner = digits_and_words();
+
+        assert_eq!(scanner.calibration_value("eightwo"), Some(82));
+        assert_eq!(scanner.calibration_value("oneight"), Some(18));
+        assert_eq!(scanner.calibration_value("twone"), Some(21));
+        assert_eq!(scanner.calibration_value("sevenine"), Some(79));
+        assert_eq!(scanner.calibration_value("nineight"), Some(98));
+        assert_eq!(scanner.calibration_value("ninine"), Some(99));
+        assert_eq!(scanner.calibration_value("nnineeightt"), Some(98));
+        assert_eq!(scanner.calibration_value("sevseven"), Some(77));
+        assert_eq!(scanner.calibration_value("abc"), None);
+    }
+
+    #[test]
+    fn test_trailing_empty_lines() {
+        assert_eq!(part_one("1abc2\n\n\n"), Some(12));
+        assert_eq!(part_two("two1nine\n\n"), Some(29));
+    }
 }
@@ -1,10 +1,34 @@
 advent_of_code::solution!(2);
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Colour {
+    Red,
+    Green,
+    Blue,
+}
+
+impl Colour {
+    const ALL: [Colour; 3] = [Colour::Red, Colour::Green, Colour::Blue];
+}
+
+/// Number of cubes of each colour, either drawn from the bag or in the bag.
+#[derive(Debug, Default, Eq, PartialEq)]
 pub struct Draw {
-    red: u32,
-    blue: u32,
-    green: u32,
+    cubes: HashMap<Colour, u32>,
+}
+
+impl Draw {
+    fn count(&self, colour: Colour) -> u32 {
+        self.cubes.get(&colour).copied().unwrap_or(0)
+    }
+}
+
+impl<const N: usize> From<[(Colour, u32); N]> for Draw {
+    fn from(cubes: [(Colour, u32); N]) -> Self {
+        Draw {
+            cubes: HashMap::from(cubes),
+        }
+    }
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -13,6 +37,10 @@ pub struct Game {
     draws: Vec<Draw>,
 }
 
+use advent_of_code::error::{At, InputError, Spanned};
+use advent_of_code::template::runner::param;
+use std::collections::HashMap;
+use std::fmt::Display;
 use std::str::FromStr;
 
 #[derive(Debug, PartialEq, Eq)]
@@ -21,56 +49,72 @@ pub enum ParseError {
     InvalidColor,
     CannotSplit(char),
     NotAGame,
+    DuplicateColor,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::NotInt => write!(f, "not an integer"),
+            ParseError::InvalidColor => write!(f, "invalid color"),
+            ParseError::CannotSplit(c) => write!(f, "expected `{c}`"),
+            ParseError::NotAGame => write!(f, "expected `Game`"),
+            ParseError::DuplicateColor => write!(f, "color already drawn"),
+        }
+    }
 }
 
-fn parse_games(s: &str) -> Result<Vec<Game>, ParseError> {
-    let games: Result<Vec<Game>, ParseError> = s
-        .split('\n')
+fn parse_games(s: &str) -> Result<Vec<Game>, InputError> {
+    s.split('\n')
         .filter(|s| !s.is_empty())
-        .map(|line| line.parse::<Game>())
-        .collect();
-    games
+        .map(|line| line.parse::<Game>().map_err(|e| e.locate(s)))
+        .collect()
 }
 
 impl FromStr for Game {
-    type Err = ParseError;
+    type Err = Spanned<ParseError>;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (game, draws) = s.split_once(':').ok_or(ParseError::CannotSplit(':'))?;
-        let (kw, id_str) = game.split_once(' ').ok_or(ParseError::CannotSplit(' '))?;
-        let id = id_str.parse::<u32>().map_err(|_| ParseError::NotInt)?;
+        let (game, draws) = s
+            .split_once(':')
+            .ok_or(ParseError::CannotSplit(':').at(s))?;
+        let (kw, id_str) = game
+            .split_once(' ')
+            .ok_or(ParseError::CannotSplit(' ').at(game))?;
+        let id = id_str
+            .parse::<u32>()
+            .map_err(|_| ParseError::NotInt.at(id_str))?;
         if kw == "Game" {
             let draws_result: Result<Vec<Draw>, Self::Err> =
                 draws.split(';').map(|d| d.parse::<Draw>()).collect();
             let draws = draws_result?;
             Ok(Game { id, draws })
         } else {
-            Err(ParseError::NotAGame)
+            Err(ParseError::NotAGame.at(kw))
         }
     }
 }
 
 impl FromStr for Draw {
-    type Err = ParseError;
+    type Err = Spanned<ParseError>;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut draw = Draw {
-            red: 0,
-            green: 0,
-            blue: 0,
-        };
+        let mut draw = Draw::default();
         for c in s.trim().split(',') {
             let (value_str, color_str) = c
                 .trim()
                 .split_once(' ')
-                .ok_or(ParseError::CannotSplit(' '))?;
+                .ok_or(ParseError::CannotSplit(' ').at(c))?;
             let value = value_str
                 .trim()
                 .parse::<u32>()
-                .map_err(|_| ParseError::NotInt)?;
-            match color_str.trim() {
-                "blue" => draw.blue = value,
-                "red" => draw.red = value,
-                "green" => draw.green = value,
-                _ => todo!(),
+                .map_err(|_| ParseError::NotInt.at(value_str))?;
+            let colour = match color_str.trim() {
+                "red" => Colour::Red,
+                "green" => Colour::Green,
+                "blue" => Colour::Blue,
+                _ => return Err(ParseError::InvalidColor.at(color_str)),
+            };
+            if draw.cubes.insert(colour, value).is_some() {
+                return Err(ParseError::DuplicateColor.at(color_str));
             }
         }
         Ok(draw)
@@ -82,52 +126,68 @@ fn valid_game(bag: &Draw, candidate: &Game) -> bool {
 }
 
 fn valid_draw(bag: &Draw, candidate: &Draw) -> bool {
-    candidate.red <= bag.red && candidate.blue <= bag.blue && candidate.green <= bag.green
+    candidate
+        .cubes
+        .iter()
+        .all(|(&colour, &count)| count <= bag.count(colour))
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let games = parse_games(input).ok()?;
-    let bag = Draw {
-        red: 12,
-        green: 13,
-        blue: 14,
-    };
-
-    let possible_games = games.iter().filter(|g| valid_game(&bag, g));
-    Some(possible_games.map(|g| g.id).sum())
+/// The bag from the puzzle, unless another is given with `--param bag="12 red, 13 green, 14 blue"`.
+fn bag() -> Result<Draw, InputError> {
+    parse_bag(param("bag"))
 }
 
-fn power(draw: &Draw) -> u32 {
-    draw.red * draw.green * draw.blue
+/// The bag described by `value`, or the one from the puzzle without a value.
+fn parse_bag(value: Option<&str>) -> Result<Draw, InputError> {
+    match value {
+        Some(bag) => bag.parse().map_err(|e: Spanned<ParseError>| {
+            e.locate(bag).context("while parsing the `bag` parameter")
+        }),
+        None => Ok(Draw::from([
+            (Colour::Red, 12),
+            (Colour::Green, 13),
+            (Colour::Blue, 14),
+        ])),
+    }
 }
 
-fn minimal_bag(game: &Game) -> Draw {
-    let red = game
-        .draws
-        .iter()
-        .max_by_key(|d| d.red)
-        .map(|d| d.red)
-        .unwrap();
-    let green = game
-        .draws
+/// Sum of the ids of the games that could have been played with `bag`.
+fn possible_games(games: &[Game], bag: &Draw) -> u32 {
+    games
         .iter()
-        .max_by_key(|d| d.green)
-        .map(|d| d.green)
-        .unwrap();
-    let blue = game
-        .draws
+        .filter(|g| valid_game(bag, g))
+        .map(|g| g.id)
+        .sum()
+}
+
+pub fn part_one(input: &str) -> Result<u32, InputError> {
+    let games = parse_games(input)?;
+
+    Ok(possible_games(&games, &bag()?))
+}
+
+fn power(draw: &Draw) -> u32 {
+    Colour::ALL
         .iter()
-        .max_by_key(|d| d.blue)
-        .map(|d| d.blue)
-        .unwrap();
+        .map(|&colour| draw.count(colour))
+        .product()
+}
 
-    Draw { red, green, blue }
+fn minimal_bag(game: &Game) -> Draw {
+    let mut bag = Draw::default();
+    for draw in &game.draws {
+        for (&colour, &count) in &draw.cubes {
+            let most = bag.cubes.entry(colour).or_default();
+            *most = (*most).max(count);
+        }
+    }
+    bag
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let games = parse_games(input).ok()?;
+pub fn part_two(input: &str) -> Result<u32, InputError> {
+    let games = parse_games(input)?;
     let minimal_bags = games.iter().map(minimal_bag);
-    Some(minimal_bags.map(|d| power(&d)).sum())
+    Ok(minimal_bags.map(|d| power(&d)).sum())
 }
 
 #[cfg(test)]
@@ -137,13 +197,13 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(8));
+        assert_eq!(result, Ok(8));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(2286));
+        assert_eq!(result, Ok(2286));
     }
 
     #[test]
@@ -151,13 +211,23 @@ mod tests {
         let result = "1 green, 2 red, 3 blue".parse::<Draw>();
         assert_eq!(
             result,
-            Ok(Draw {
-                red: 2,
-                green: 1,
-                blue: 3
-            })
+            Ok(Draw::from([
+                (Colour::Red, 2),
+                (Colour::Green, 1),
+                (Colour::Blue, 3)
+            ]))
         );
     }
+
+    #[test]
+    fn parse_error_location() {
+        let input = "Game 1: 3 blue\nGame 2: 1 blue, x red\n";
+        let error = parse_games(input).unwrap_err();
+
+        assert_eq!(error.message(), "not an integer");
+        assert_eq!(error.location(), Some((2, 17)));
+    }
+
     #[test]
     fn parse_game() {
         let input = "Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red";
@@ -167,23 +237,57 @@ mod tests {
             Ok(Game {
                 id: 4,
                 draws: vec!(
-                    Draw {
-                        red: 3,
-                        green: 1,
-                        blue: 6
-                    },
-                    Draw {
-                        green: 3,
-                        red: 6,
-                        blue: 0
-                    },
-                    Draw {
-                        green: 3,
-                        blue: 15,
-                        red: 14
-                    }
+                    Draw::from([(Colour::Red, 3), (Colour::Green, 1), (Colour::Blue, 6)]),
+                    Draw::from([(Colour::Green, 3), (Colour::Red, 6)]),
+                    Draw::from([(Colour::Green, 3), (Colour::Blue, 15), (Colour::Red, 14)])
                 )
             })
         );
     }
+
+    #[test]
+    fn parse_invalid_color() {
+        let input = "Game 1: 3 blue\nGame 2: 1 blue, 2 purple\n";
+        let error = parse_games(input).unwrap_err();
+
+        assert_eq!(error.message(), "invalid color");
+        assert_eq!(error.location(), Some((2, 19)));
+    }
+
+    #[test]
+    fn parse_duplicate_color() {
+        let input = "Game 1: 3 blue, 1 red, 2 blue\n";
+        let error = parse_games(input).unwrap_err();
+
+        assert_eq!(error.message(), "color already drawn");
+        assert_eq!(error.location(), Some((1, 26)));
+    }
+
+    #[test]
+    fn test_parse_bag() {
+        let default = Draw::from([(Colour::Red, 12), (Colour::Green, 13), (Colour::Blue, 14)]);
+        assert_eq!(parse_bag(None), Ok(default));
+        assert_eq!(
+            parse_bag(Some("20 red, 15 blue")),
+            Ok(Draw::from([(Colour::Red, 20), (Colour::Blue, 15)]))
+        );
+
+        let error = parse_bag(Some("20 red, many blue")).unwrap_err();
+        assert_eq!(error.message(), "not an integer");
+        assert_eq!(error.location(), Some((1, 9)));
+        assert!(error
+            .to_string()
+            .contains("while parsing the `bag` parameter"));
+    }
+
+    #[test]
+    fn test_possible_games_with_other_bag() {
+        let games = parse_games(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+
+        let bag = "20 red, 13 green, 15 blue".parse::<Draw>().unwrap();
+        assert_eq!(possible_games(&games, &bag), 15);
+
+        let bag = "4 red, 3 green".parse::<Draw>().unwrap();
+        assert_eq!(possible_games(&games, &bag), 0);
+    }
 }
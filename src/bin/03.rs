@@ -17,6 +17,8 @@ pub enum Entity {
 
 struct Entities(Vec<Entity>);
 
+use advent_of_code::error::{At, InputError, Spanned};
+use std::fmt::Display;
 pub(crate) use std::str::FromStr;
 
 #[derive(Debug, PartialEq, Eq)]
@@ -24,8 +26,16 @@ pub enum ParseError {
     NotInt,
 }
 
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::NotInt => write!(f, "not an integer"),
+        }
+    }
+}
+
 impl FromStr for Entities {
-    type Err = ParseError;
+    type Err = Spanned<ParseError>;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut entities: Vec<Entity> = Vec::new();
         let lines: Vec<&str> = s.split('\n').collect();
@@ -46,12 +56,12 @@ impl FromStr for Entities {
                     }
                     (_, true) => {
                         if !in_number {
-                            let number_str: String = line[x..]
-                                .chars()
-                                .take_while(|c| c.is_ascii_digit())
-                                .collect();
-                            let number =
-                                number_str.parse::<u32>().map_err(|_| ParseError::NotInt)?;
+                            let digits =
+                                line[x..].bytes().take_while(|c| c.is_ascii_digit()).count();
+                            let number_str = &line[x..x + digits];
+                            let number = number_str
+                                .parse::<u32>()
+                                .map_err(|_| ParseError::NotInt.at(number_str))?;
                             let ent = Entity::Part(PartNumber {
                                 number,
                                 start_pos: (x, y),
@@ -93,8 +103,12 @@ pub fn is_adjacent_to_any(pn: &PartNumber, symbols: &[&Coord]) -> bool {
     symbols.iter().any(|&s| is_adjacent_to_one(pn, s))
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let Entities(entities) = input.parse::<Entities>().ok()?;
+fn parse(input: &str) -> Result<Entities, InputError> {
+    input.parse::<Entities>().map_err(|e| e.locate(input))
+}
+
+pub fn part_one(input: &str) -> Result<u32, InputError> {
+    let Entities(entities) = parse(input)?;
 
     let symbols: Vec<&Coord> = entities
         .iter()
@@ -111,24 +125,22 @@ pub fn part_one(input: &str) -> Option<u32> {
         })
         .collect();
 
-    Some(
-        entities
-            .iter()
-            .filter_map(|e| {
-                if let Entity::Part(partnumber) = e {
-                    Some(partnumber)
-                } else {
-                    None
-                }
-            })
-            .filter(|&pn| is_adjacent_to_any(pn, &symbols))
-            .map(|e| e.number)
-            .sum(),
-    )
+    Ok(entities
+        .iter()
+        .filter_map(|e| {
+            if let Entity::Part(partnumber) = e {
+                Some(partnumber)
+            } else {
+                None
+            }
+        })
+        .filter(|&pn| is_adjacent_to_any(pn, &symbols))
+        .map(|e| e.number)
+        .sum())
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let Entities(entities) = input.parse::<Entities>().ok()?;
+pub fn part_two(input: &str) -> Result<u32, InputError> {
+    let Entities(entities) = parse(input)?;
 
     let gears: Vec<&Coord> = entities
         .iter()
@@ -156,22 +168,20 @@ pub fn part_two(input: &str) -> Option<u32> {
         })
         .collect();
 
-    Some(
-        gears
-            .iter()
-            .filter_map(|&g| {
-                let closest: Vec<&&PartNumber> = parts
-                    .iter()
-                    .filter(|&part| is_adjacent_to_one(part, g))
-                    .collect();
-                if closest.len() == 2 {
-                    Some(closest[0].number * closest[1].number)
-                } else {
-                    None
-                }
-            })
-            .sum(),
-    )
+    Ok(gears
+        .iter()
+        .filter_map(|&g| {
+            let closest: Vec<&&PartNumber> = parts
+                .iter()
+                .filter(|&part| is_adjacent_to_one(part, g))
+                .collect();
+            if closest.len() == 2 {
+                Some(closest[0].number * closest[1].number)
+            } else {
+                None
+            }
+        })
+        .sum())
 }
 
 #[cfg(test)]
@@ -181,19 +191,19 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(4361));
+        assert_eq!(result, Ok(4361));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(467835));
+        assert_eq!(result, Ok(467835));
     }
 
     #[test]
     fn test_parse() {
         let input = advent_of_code::template::read_file("examples", DAY);
-        let Entities(result) = input.parse::<Entities>().expect("should parse");
+        let Entities(result) = parse(&input).expect("should parse");
         let expected = vec![
             Entity::Part(PartNumber {
                 number: 467,
@@ -230,6 +240,15 @@ mod tests {
         assert_eq!(result[..6], expected);
     }
 
+    #[test]
+    fn test_parse_error_location() {
+        let input = "467..114..\n...*......\n..99999999999..\n";
+        let error = parse(input).err().expect("should not parse");
+
+        assert_eq!(error.location(), Some((3, 3)));
+        assert!(error.to_string().ends_with("  |   ^^^^^^^^^^^"));
+    }
+
     #[test]
     fn test_is_adjacent_to_one() {
         let part_number = PartNumber {
@@ -1,8 +1,11 @@
 use std::{
     collections::{HashMap, HashSet},
+    fmt::Display,
     num::ParseIntError,
 };
 
+use advent_of_code::error::{At, InputError, Spanned};
+
 advent_of_code::solution!(4);
 
 #[derive(Debug, PartialEq, Eq)]
@@ -19,28 +22,37 @@ pub enum ParseError {
     NotInt2,
 }
 
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::InvalidStructure => write!(f, "invalid card structure"),
+            ParseError::NotInt(e) => write!(f, "not an integer: {e}"),
+            ParseError::NotInt2 => write!(f, "not an integer"),
+        }
+    }
+}
+
 impl std::str::FromStr for Card {
-    type Err = ParseError;
+    type Err = Spanned<ParseError>;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let rest = s.strip_prefix("Card").ok_or(ParseError::InvalidStructure)?;
-        let (card_num_str, rest) = rest.split_once(':').ok_or(ParseError::InvalidStructure)?;
-
+        let rest = s
+            .strip_prefix("Card")
+            .ok_or(ParseError::InvalidStructure.at(s))?;
+        let (card_num_str, rest) = rest
+            .split_once(':')
+            .ok_or(ParseError::InvalidStructure.at(rest))?;
+
+        let card_num_str = card_num_str.trim();
         let card_num = card_num_str
-            .trim()
             .parse::<u16>()
-            .map_err(ParseError::NotInt)?;
+            .map_err(|e| ParseError::NotInt(e).at(card_num_str))?;
 
-        let (winners_strs, drawn_strs) =
-            rest.split_once('|').ok_or(ParseError::InvalidStructure)?;
+        let (winners_strs, drawn_strs) = rest
+            .split_once('|')
+            .ok_or(ParseError::InvalidStructure.at(rest))?;
 
-        let winners = u8s(winners_strs)
-            .map_err(ParseError::NotInt)?
-            .into_iter()
-            .collect();
-        let drawn = u8s(drawn_strs)
-            .map_err(ParseError::NotInt)?
-            .into_iter()
-            .collect();
+        let winners = u8s(winners_strs)?.into_iter().collect();
+        let drawn = u8s(drawn_strs)?.into_iter().collect();
 
         Ok(Card {
             number: card_num,
@@ -50,35 +62,32 @@ impl std::str::FromStr for Card {
     }
 }
 
-fn u8s(s: &str) -> Result<Vec<u8>, ParseIntError> {
-    s.trim()
-        .split(' ')
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty())
-        .map(|s| s.trim().parse::<u8>())
+fn u8s(s: &str) -> Result<Vec<u8>, Spanned<ParseError>> {
+    s.split_whitespace()
+        .map(|s| s.parse::<u8>().map_err(|e| ParseError::NotInt(e).at(s)))
         .collect()
 }
 
-fn parse_cards(s: &str) -> Result<Vec<Card>, ParseError> {
-    s.lines().map(|line| line.parse::<Card>()).collect()
+fn parse_cards(s: &str) -> Result<Vec<Card>, InputError> {
+    s.lines()
+        .map(|line| line.parse::<Card>().map_err(|e| e.locate(s)))
+        .collect()
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let cards = parse_cards(input).expect("parseeee");
-
-    Some(
-        cards
-            .into_iter()
-            .map(|card| {
-                let count = card.matches();
-                if count > 0 {
-                    1 << (count - 1)
-                } else {
-                    0
-                }
-            })
-            .sum(),
-    )
+pub fn part_one(input: &str) -> Result<u32, InputError> {
+    let cards = parse_cards(input)?;
+
+    Ok(cards
+        .into_iter()
+        .map(|card| {
+            let count = card.matches();
+            if count > 0 {
+                1 << (count - 1)
+            } else {
+                0
+            }
+        })
+        .sum())
 }
 
 impl Card {
@@ -89,8 +98,8 @@ impl Card {
     }
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let cards = parse_cards(input).expect("parseeee");
+pub fn part_two(input: &str) -> Result<u32, InputError> {
+    let cards = parse_cards(input)?;
 
     let min = cards
         .iter()
@@ -120,7 +129,7 @@ pub fn part_two(input: &str) -> Option<u32> {
         }
     }
 
-    Some(card_count.values().sum())
+    Ok(card_count.values().sum())
 }
 
 #[cfg(test)]
@@ -130,7 +139,7 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(13));
+        assert_eq!(result, Ok(13));
     }
 
     #[test]
@@ -138,7 +147,7 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file_part(
             "examples", DAY, 2,
         ));
-        assert_eq!(result, Some(30));
+        assert_eq!(result, Ok(30));
     }
 
     #[test]
@@ -156,4 +165,13 @@ mod tests {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_parse_error_location() {
+        let input = "Card 1: 41 48 | 83 86\nCard 2: 13 320 | 61 30\n";
+        let error = parse_cards(input).unwrap_err();
+
+        assert_eq!(error.location(), Some((2, 12)));
+        assert!(error.message().starts_with("not an integer"));
+    }
 }
@@ -0,0 +1,188 @@
+use advent_of_code::error::{Context, InputError};
+use advent_of_code::interval::{Interval, IntervalSet, RangeMap};
+use advent_of_code::parse::{self, ErrorKind, ParseError};
+
+advent_of_code::solution!(5);
+
+#[derive(Debug, PartialEq)]
+struct Map<'a> {
+    from: &'a str,
+    to: &'a str,
+    ranges: RangeMap<u64>,
+}
+
+#[derive(Debug, PartialEq)]
+struct Almanac<'a> {
+    seeds: Vec<u64>,
+    /// The seed numbers after `seeds:`, to point errors about them at.
+    seeds_at: &'a str,
+    maps: Vec<Map<'a>>,
+}
+
+fn parse(input: &str) -> Result<Almanac<'_>, InputError> {
+    let mut sections = parse::sections(input);
+
+    let seeds_str = sections.next().unwrap_or(input);
+    let seeds_at = parse::literal(seeds_str, "seeds:")
+        .map_err(|e| e.locate(input))?
+        .trim_end();
+    let seeds = parse::uints(seeds_at).map_err(|e| e.locate(input))?;
+
+    let maps = sections
+        .map(|section| {
+            let header = section.lines().next().unwrap_or(section);
+            parse_map(section)
+                .map_err(|e| e.locate(input))
+                .with_context(|| format!("while parsing map `{header}`"))
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(Almanac {
+        seeds,
+        seeds_at,
+        maps,
+    })
+}
+
+/// Parses a section like `seed-to-soil map:` followed by `destination source length` lines.
+fn parse_map(section: &str) -> parse::Result<'_, Map<'_>> {
+    let mut lines = section.lines();
+    let header = lines.next().unwrap_or(section);
+
+    let name = header
+        .strip_suffix(" map:")
+        .ok_or_else(|| ParseError::new(ErrorKind::Expected(" map:".into()), header))?;
+    let (from, to) = name
+        .split_once("-to-")
+        .ok_or_else(|| ParseError::new(ErrorKind::Expected("-to-".into()), name))?;
+
+    let ranges = lines
+        .map(|line| match parse::uints::<u64>(line)?[..] {
+            [destination, source, len] => Ok((Interval::from_len(source, len), destination)),
+            _ => Err(ParseError::new(
+                ErrorKind::Expected("destination, source and length".into()),
+                line,
+            )),
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(Map { from, to, ranges })
+}
+
+pub fn part_one(input: &str) -> Result<u64, InputError> {
+    let almanac = parse(input)?;
+
+    almanac
+        .seeds
+        .iter()
+        .map(|&seed| almanac.maps.iter().fold(seed, |v, map| map.ranges.get(v)))
+        .min()
+        .ok_or_else(|| InputError::new(input, almanac.seeds_at, "expected at least one seed"))
+}
+
+pub fn part_two(input: &str) -> Result<u64, InputError> {
+    let almanac = parse(input)?;
+
+    let pairs = almanac.seeds.chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        let unpaired = almanac
+            .seeds_at
+            .rsplit(char::is_whitespace)
+            .next()
+            .unwrap_or(almanac.seeds_at);
+        return Err(InputError::new(
+            input,
+            unpaired,
+            "expected seeds in pairs of start and length",
+        ));
+    }
+    let seeds: IntervalSet<u64> = pairs
+        .map(|pair| Interval::from_len(pair[0], pair[1]))
+        .collect();
+
+    let locations = almanac
+        .maps
+        .iter()
+        .fold(seeds, |set, map| map.ranges.get_set(&set));
+
+    locations
+        .min()
+        .ok_or_else(|| InputError::new(input, almanac.seeds_at, "expected at least one seed"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_example() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let result = parse(&input).expect("should parse");
+
+        let first_map = Map {
+            from: "seed",
+            to: "soil",
+            ranges: [
+                (Interval::from_len(98, 2), 50),
+                (Interval::from_len(50, 48), 52),
+            ]
+            .into_iter()
+            .collect(),
+        };
+
+        assert_eq!(result.seeds, vec![79, 14, 55, 13]);
+        assert_eq!(result.maps.len(), 7);
+        assert_eq!(result.maps[0], first_map);
+        assert_eq!(
+            (result.maps[6].from, result.maps[6].to),
+            ("humidity", "location")
+        );
+    }
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Ok(35));
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Ok(46));
+    }
+
+    #[test]
+    fn test_part_two_unpaired_seed() {
+        // The seeds can be on any line, and span several.
+        let input = "\nseeds: 79 14\n  55\n\nseed-to-soil map:\n50 98 2\n";
+        let error = part_two(input).unwrap_err();
+
+        assert_eq!(
+            error.message(),
+            "expected seeds in pairs of start and length"
+        );
+        assert_eq!(error.location(), Some((3, 3)));
+    }
+
+    #[test]
+    fn test_no_seeds() {
+        let input = "\nseeds:\n\nseed-to-soil map:\n50 98 2\n";
+
+        for error in [part_one(input).unwrap_err(), part_two(input).unwrap_err()] {
+            assert_eq!(error.message(), "expected at least one seed");
+            assert_eq!(error.location(), Some((2, 7)));
+        }
+    }
+
+    #[test]
+    fn test_parse_error_location() {
+        let input = "seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n52 50\n";
+        let error = parse(input).unwrap_err();
+
+        assert_eq!(error.message(), "expected `destination, source and length`");
+        assert_eq!(error.location(), Some((5, 1)));
+        assert!(error
+            .to_string()
+            .ends_with("= while parsing map `seed-to-soil map:`"));
+    }
+}
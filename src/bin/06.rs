@@ -1,9 +1,11 @@
+use advent_of_code::{math::isqrt, parse};
+
 advent_of_code::solution!(6);
 
 #[derive(Debug, PartialEq)]
 struct Races {
-    time: Vec<u32>,
-    distance: Vec<u32>,
+    time: Vec<u64>,
+    distance: Vec<u64>,
 }
 
 fn parse(input: &str) -> Option<Races> {
@@ -11,72 +13,67 @@ fn parse(input: &str) -> Option<Races> {
 
     assert_eq!(lines.len(), 2);
 
-    let time_str = lines[0].strip_prefix("Time:")?.trim();
-    let distance_str = lines[1].strip_prefix("Distance:")?.trim();
-
-    let time = u32s(time_str).ok()?;
-    let distance = u32s(distance_str).ok()?;
+    let time = parse::uints(parse::literal(lines[0], "Time:").ok()?).ok()?;
+    let distance = parse::uints(parse::literal(lines[1], "Distance:").ok()?).ok()?;
 
     assert_eq!(time.len(), distance.len());
 
     Some(Races { time, distance })
 }
 
-fn u32s(s: &str) -> Result<Vec<u32>, std::num::ParseIntError> {
-    s.trim()
-        .split(' ')
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty())
-        .map(|s| s.trim().parse::<u32>())
-        .collect()
+/// Number of hold times `h` for which `(time - h) * h > distance`.
+///
+/// The winning hold times are the integers strictly between the roots of `h² - time·h + distance`,
+/// symmetric around `time / 2`. The lower root is found with an integer square root and then nudged
+/// onto the first winning hold time, so no floating point rounding is involved.
+fn winning_hold_times(time: u64, distance: u64) -> u64 {
+    let beats = |hold: u64| u128::from(time - hold) * u128::from(hold) > u128::from(distance);
+
+    let (t, d) = (u128::from(time), u128::from(distance));
+    let Some(discriminant) = (t * t).checked_sub(4 * d) else {
+        return 0;
+    };
+
+    let half = time / 2;
+    let mut min = ((t - isqrt(discriminant)) / 2) as u64;
+    while min <= half && !beats(min) {
+        min += 1;
+    }
+    if min > half {
+        return 0;
+    }
+    while min > 0 && beats(min - 1) {
+        min -= 1;
+    }
+
+    time - 2 * min + 1
+}
+
+/// Glues the digits of all values together, e.g. `[7, 15, 30]` becomes `71530`.
+fn kerned(values: &[u64]) -> u64 {
+    values.iter().fold(0, |acc, &v| {
+        let digits = v.checked_ilog10().unwrap_or(0) + 1;
+        acc * 10u64.pow(digits) + v
+    })
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
+pub fn part_one(input: &str) -> Option<u64> {
     let races = parse(input).expect("parses");
-    let mut result = 1;
-    for (time, distance) in std::iter::zip(races.time, races.distance) {
-        let hold_times: Vec<u32> = (1..time)
-            .filter(|hold_time| (time - hold_time) * hold_time > distance)
-            .collect();
-
-        let min = hold_times.iter().min().expect("to have minimum");
-        let max = hold_times.iter().max().expect("to have maximum");
-        let delta = max - min + 1;
-        result *= delta;
-    }
 
-    Some(result)
+    Some(
+        std::iter::zip(races.time, races.distance)
+            .map(|(time, distance)| winning_hold_times(time, distance))
+            .product(),
+    )
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
+pub fn part_two(input: &str) -> Option<u64> {
     let races = parse(input).expect("parses");
 
-    let distance_str = races
-        .distance
-        .iter()
-        .map(|d| format!("{}", d))
-        .collect::<Vec<_>>()
-        .join("");
-    let time_str = races
-        .time
-        .iter()
-        .map(|d| format!("{}", d))
-        .collect::<Vec<_>>()
-        .join("");
-
-    let distance = distance_str.parse::<u64>().expect("can parse distance");
-    let time = time_str.parse::<u64>().expect("can parse time");
-
-    let min = (1..time)
-        .find(|hold_time| (time - hold_time) * hold_time > distance)
-        .expect("to have minimum");
-    let max = (1..time)
-        .rev()
-        .find(|hold_time| (time - hold_time) * hold_time > distance)
-        .expect("to have maximum");
-    let delta = max - min + 1;
-
-    Some(delta as u32)
+    Some(winning_hold_times(
+        kerned(&races.time),
+        kerned(&races.distance),
+    ))
 }
 
 #[cfg(test)]
@@ -97,6 +94,22 @@ mod tests {
         assert_eq!(result, Some(71503));
     }
 
+    #[test]
+    fn test_winning_hold_times() {
+        assert_eq!(winning_hold_times(7, 9), 4);
+        assert_eq!(winning_hold_times(15, 40), 8);
+        assert_eq!(winning_hold_times(30, 200), 9);
+        assert_eq!(winning_hold_times(4, 4), 0);
+        assert_eq!(winning_hold_times(1, 0), 0);
+    }
+
+    #[test]
+    fn test_kerned() {
+        assert_eq!(kerned(&[7, 15, 30]), 71530);
+        assert_eq!(kerned(&[9, 40, 200]), 940200);
+        assert_eq!(kerned(&[0, 1]), 1);
+    }
+
     #[test]
     fn parse_example() {
         let result =
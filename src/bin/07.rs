@@ -1,10 +1,12 @@
+use advent_of_code::error::{At, InputError, Spanned};
 use itertools::Itertools;
+use std::collections::HashMap;
 use std::{
     cmp::{Ord, Ordering},
+    fmt::Display,
     hash::Hash,
     num::ParseIntError,
 };
-use std::collections::{HashMap};
 
 advent_of_code::solution!(7);
 
@@ -205,63 +207,82 @@ enum ParseError {
     StructuralProblem,
 }
 
-fn parse(lines: &str) -> Result<Vec<Input>, ParseError> {
-    lines.lines().map(|l| l.parse::<Input>()).collect()
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::InvalidCard(c) => write!(f, "invalid card `{c}`"),
+            ParseError::HandLengthInvalid(len) => {
+                write!(f, "hand should have 5 cards, found {len}")
+            }
+            ParseError::BidInvalid(e) => write!(f, "invalid bid: {e}"),
+            ParseError::StructuralProblem => write!(f, "expected a hand and a bid"),
+        }
+    }
+}
+
+fn parse(lines: &str) -> Result<Vec<Input>, InputError> {
+    lines
+        .lines()
+        .map(|l| {
+            l.parse::<Input>().map_err(|e| {
+                let hand = l.split(' ').next().unwrap_or(l);
+                e.locate(lines)
+                    .context(format!("while parsing hand `{hand}`"))
+            })
+        })
+        .collect()
 }
 
 impl std::str::FromStr for Input {
-    type Err = ParseError;
+    type Err = Spanned<ParseError>;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (hand_str, bid_str) = s.split_once(' ').ok_or(ParseError::StructuralProblem)?;
+        let (hand_str, bid_str) = s
+            .split_once(' ')
+            .ok_or(ParseError::StructuralProblem.at(s))?;
 
         let hand = hand_str
-            .chars()
-            .map(|c| parse_card(c).ok_or(ParseError::InvalidCard(c)))
+            .char_indices()
+            .map(|(i, c)| {
+                parse_card(c)
+                    .ok_or_else(|| ParseError::InvalidCard(c).at(&hand_str[i..i + c.len_utf8()]))
+            })
             .collect::<Result<Vec<_>, Self::Err>>()?;
 
         let bid = bid_str
             .parse::<u32>()
-            .map_err(ParseError::BidInvalid)?;
+            .map_err(|e| ParseError::BidInvalid(e).at(bid_str))?;
 
         if hand_str.len() != 5 {
-            return Err(ParseError::HandLengthInvalid(hand_str.len()));
+            return Err(ParseError::HandLengthInvalid(hand_str.len()).at(hand_str));
         }
 
         Ok(Input(Hand(hand), bid))
     }
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let mut inputs = parse(input).expect("parsable input");
+pub fn part_one(input: &str) -> Result<u32, InputError> {
+    let mut inputs = parse(input)?;
 
-    inputs.sort_by(|Input(a_hand, _), Input(b_hand, _)| {
-        order_hands(a_hand, b_hand)
-    });
+    inputs.sort_by(|Input(a_hand, _), Input(b_hand, _)| order_hands(a_hand, b_hand));
 
-    Some(
-        inputs
-            .into_iter()
-            .enumerate()
-            .map(|(index, Input(_, bid))| (index as u32 + 1) * bid)
-            .sum(),
-    )
+    Ok(inputs
+        .into_iter()
+        .enumerate()
+        .map(|(index, Input(_, bid))| (index as u32 + 1) * bid)
+        .sum())
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let normal_inputs = parse(input).expect("parsable input");
+pub fn part_two(input: &str) -> Result<u32, InputError> {
+    let normal_inputs = parse(input)?;
     let mut inputs: Vec<_> = normal_inputs.iter().map(convert_to_joker).collect();
 
-    inputs.sort_by(|JokerInput(a_hand, _), JokerInput(b_hand, _)| {
-        order_hands(a_hand, b_hand)
-    });
-
-    Some(
-        inputs
-            .into_iter()
-            .enumerate()
-            .map(|(index, JokerInput(_, bid))| (index as u32 + 1) * bid)
-            .sum(),
-    )
+    inputs.sort_by(|JokerInput(a_hand, _), JokerInput(b_hand, _)| order_hands(a_hand, b_hand));
+
+    Ok(inputs
+        .into_iter()
+        .enumerate()
+        .map(|(index, JokerInput(_, bid))| (index as u32 + 1) * bid)
+        .sum())
 }
 
 #[cfg(test)]
@@ -272,13 +293,13 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(6440));
+        assert_eq!(result, Ok(6440));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(5905));
+        assert_eq!(result, Ok(5905));
     }
 
     #[test]
@@ -291,6 +312,17 @@ mod tests {
         assert_eq!(result[0], expected);
     }
 
+    #[test]
+    fn test_parse_error_diagnostic() {
+        let input = "32T3K 765\nT55X5 684\n";
+        let error = parse(input).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "error: invalid card `X`\n --> 2:4\n  |\n2 | T55X5 684\n  |    ^\n  = while parsing hand `T55X5`"
+        );
+    }
+
     #[test]
     fn test_joker_rank() {
         use JokerCard::*;
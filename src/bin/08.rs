@@ -1,4 +1,9 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt::Display};
+
+use advent_of_code::cycle::{self, Cycle};
+use advent_of_code::error::{At, InputError, Spanned};
+use advent_of_code::math::crt;
+use itertools::Itertools;
 
 advent_of_code::solution!(8);
 
@@ -29,7 +34,6 @@ struct Node {
     right: NodeId,
 }
 
-
 #[derive(Debug)]
 struct Input {
     directions: Vec<Direction>,
@@ -44,49 +48,71 @@ enum ParseError {
     InvalidStructure,
 }
 
-fn parse(input: &str) -> Result<Input, ParseError> {
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::DirectionInvalid(c) => write!(f, "invalid direction `{c}`"),
+            ParseError::UnexpectedChar { expected } => write!(f, "expected `{expected}`"),
+            ParseError::NodeIdTooLong(s) => write!(f, "node id `{s}` should be 3 characters"),
+            ParseError::InvalidStructure => {
+                write!(f, "expected directions, a blank line and then nodes")
+            }
+        }
+    }
+}
+
+fn parse(input: &str) -> Result<Input, InputError> {
+    parse_spanned(input).map_err(|e| e.locate(input))
+}
+
+fn parse_spanned(input: &str) -> Result<Input, Spanned<ParseError>> {
     use ParseError::*;
     let mut lines = input.lines();
-    let directions_str = lines.next().ok_or(InvalidStructure)?;
+    let directions_str = lines.next().ok_or(InvalidStructure.at(input))?;
 
     let directions: Vec<Direction> = directions_str
-        .chars()
-        .map(|c| match c {
+        .char_indices()
+        .map(|(i, c)| match c {
             'L' => Ok(Direction::Left),
             'R' => Ok(Direction::Right),
-            c => Err(DirectionInvalid(c)),
+            c => Err(DirectionInvalid(c).at(&directions_str[i..i + c.len_utf8()])),
         })
-        .collect::<Result<Vec<_>, ParseError>>()?;
+        .collect::<Result<Vec<_>, _>>()?;
 
-    let blank = lines.next().ok_or(InvalidStructure)?;
+    let blank = lines.next().ok_or(InvalidStructure.at(directions_str))?;
 
     if !blank.is_empty() {
-        return Err(InvalidStructure);
+        return Err(InvalidStructure.at(blank));
     }
 
     let graph: HashMap<NodeId, Node> = lines
         .map(parse_graph_entry)
-        .collect::<Result<HashMap<NodeId, Node>, ParseError>>()?;
+        .collect::<Result<HashMap<NodeId, Node>, _>>()?;
 
     Ok(Input { directions, graph })
 }
 
-fn parse_graph_entry(line: &str) -> Result<(NodeId, Node), ParseError> {
+fn parse_graph_entry(line: &str) -> Result<(NodeId, Node), Spanned<ParseError>> {
     use ParseError::*;
 
-    let (node_id_str, node_str) = line.split_once('=').ok_or(InvalidStructure)?;
+    let (node_id_str, node_str) = line.split_once('=').ok_or(InvalidStructure.at(line))?;
 
     let node_id = parse_node_id(node_id_str.trim())?;
 
+    let node_str = node_str.trim();
     let (left_node_str, right_node_str) = node_str
-        .trim()
         .strip_prefix('(')
-        .ok_or(UnexpectedChar { expected: '(' })?
+        .ok_or(UnexpectedChar { expected: '(' }.at(node_str))?
         .split_once(',')
-        .ok_or(UnexpectedChar { expected: ',' })?;
+        .ok_or(UnexpectedChar { expected: ',' }.at(node_str))?;
+
+    let right_node_str = right_node_str.trim();
+    let right_node_str = right_node_str
+        .strip_suffix(')')
+        .ok_or(UnexpectedChar { expected: ')' }.at(&right_node_str[right_node_str.len()..]))?;
 
     let left_node = parse_node_id(left_node_str.trim())?;
-    let right_node = parse_node_id(&right_node_str.trim()[..3])?;
+    let right_node = parse_node_id(right_node_str)?;
 
     Ok((
         node_id,
@@ -97,9 +123,9 @@ fn parse_graph_entry(line: &str) -> Result<(NodeId, Node), ParseError> {
     ))
 }
 
-fn parse_node_id(s: &str) -> Result<NodeId, ParseError> {
-    if s.len() != 3 {
-        return Err(ParseError::NodeIdTooLong(s.to_owned()));
+fn parse_node_id(s: &str) -> Result<NodeId, Spanned<ParseError>> {
+    if s.chars().count() != 3 {
+        return Err(ParseError::NodeIdTooLong(s.to_owned()).at(s));
     }
 
     let mut chars = s.chars();
@@ -110,8 +136,8 @@ fn parse_node_id(s: &str) -> Result<NodeId, ParseError> {
     ))
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let input = parse(input).expect("should parse");
+pub fn part_one(input: &str) -> Result<u32, InputError> {
+    let input = parse(input)?;
 
     let mut current_node: &NodeId = &parse_node_id("AAA").unwrap();
 
@@ -130,88 +156,92 @@ pub fn part_one(input: &str) -> Option<u32> {
         };
     }
 
-    Some(count)
+    Ok(count)
 }
 
-#[derive(Debug)]
-struct Recorder {
-    period: Option<usize>,
+/// A ghost's walk, with the steps at which it stands on a `Z` node.
+#[derive(Debug, PartialEq)]
+struct Walk {
+    cycle: Cycle,
+    /// Steps before `cycle.prefix + cycle.length` ending on a `Z` node; those at or after
+    /// `cycle.prefix` recur every `cycle.length` steps.
+    done_at: Vec<usize>,
 }
 
-impl Recorder {
-    fn new() -> Recorder {
-        Recorder { period: None}
-    }
+impl Walk {
+    fn from(input: &Input, start: &NodeId) -> Walk {
+        let history = cycle::find((start, 0), |&(node, i)| {
+            let next = input.graph.get(node).expect("to find next node");
+            let next = match input.directions[i] {
+                Direction::Left => &next.left,
+                Direction::Right => &next.right,
+            };
+            (next, (i + 1) % input.directions.len())
+        });
 
-    fn record(&mut self, count: usize) {
-        if self.period.is_none() {
-            self.period = Some(count);
+        let done_at = history
+            .states()
+            .iter()
+            .enumerate()
+            .filter(|(_, (node, _))| node.is_done())
+            .map(|(count, _)| count)
+            .collect();
+
+        Walk {
+            cycle: history.cycle,
+            done_at,
         }
     }
 
-    fn is_done(&self) -> bool {
-        self.period.is_some()
+    fn is_done_at(&self, count: usize) -> bool {
+        self.done_at.contains(&self.cycle.equivalent_step(count))
     }
-}
 
-pub fn lcm(nums: &[usize]) -> usize {
-    if nums.len() == 1 {
-        return nums[0];
+    /// `(offset, period)` congruences of the steps in the cycle ending on a `Z` node.
+    fn recurring(&self) -> impl Iterator<Item = (i64, i64)> + '_ {
+        let period = self.cycle.length as i64;
+        self.done_at
+            .iter()
+            .filter(|&&count| count >= self.cycle.prefix)
+            .map(move |&count| (count as i64, period))
     }
-    let a = nums[0];
-    let b = lcm(&nums[1..]);
-    a * b / gcd_of_two_numbers(a, b)
 }
 
-fn gcd_of_two_numbers(a: usize, b: usize) -> usize {
-    if b == 0 {
-        return a;
-    }
-    gcd_of_two_numbers(b, a % b)
-}
+pub fn part_two(input: &str) -> Result<usize, InputError> {
+    let network = parse(input)?;
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let input = parse(input).expect("should parse");
+    let mut start_nodes: Vec<&NodeId> = network.graph.keys().filter(|n| n.is_start()).collect();
+    start_nodes.sort();
 
-    let mut current_nodes: Vec<&NodeId> = input
-        .graph
-        .keys()
-        .filter(|n| n.is_start())
+    let walks: Vec<Walk> = start_nodes
+        .iter()
+        .map(|n| Walk::from(&network, n))
         .collect();
-    current_nodes.sort();
-
-    let mut recordings : Vec<Recorder> = current_nodes.iter().map(|_| Recorder::new()).collect();
-
-    let goal = current_nodes.len();
 
-    for (count, direction) in input.directions.iter().cycle().enumerate() {
-        let num_done = recordings
-            .iter()
-            .filter(|n| n.is_done())
-            .count();
-
-        for (i, node) in current_nodes.iter().enumerate() {
-            if node.is_done() {
-                recordings[i].record(count);
-            }
-        }
-
-        if num_done == goal {
-            break;
-        }
-
-        for current_node in current_nodes.iter_mut() {
-            let node = input.graph.get(current_node).expect("to find next node");
-            *current_node = match direction {
-                Direction::Left => &node.left,
-                Direction::Right => &node.right,
-            };
-        }
+    // Before every ghost has entered its cycle, just check each step.
+    let settled = walks.iter().map(|w| w.cycle.prefix).max().unwrap_or(0);
+    if let Some(count) = (0..settled).find(|&c| walks.iter().all(|w| w.is_done_at(c))) {
+        return Ok(count);
     }
 
-    let divisors : Vec<usize> = recordings.iter().filter_map(|r| r.period).collect();
+    // Afterwards, every combination of recurring `Z` steps is a system of congruences.
+    let solution = walks
+        .iter()
+        .map(|w| w.recurring().collect::<Vec<_>>())
+        .multi_cartesian_product()
+        .filter_map(|congruences| {
+            let latest = congruences.iter().map(|&(count, _)| count).max()?;
+            let (count, period) = crt(congruences.iter().map(|&(c, p)| (c % p, p)))?;
+            // The first solution at or after the latest offset, where all ghosts are cycling.
+            let behind = (latest - count).max(0);
+            Some(count + (behind + period - 1) / period * period)
+        })
+        .min()
+        .ok_or_else(|| {
+            InputError::unlocated("the ghosts never end on `Z` nodes at the same time")
+        })?;
 
-    Some(lcm(&divisors))
+    Ok(solution as usize)
 }
 
 #[cfg(test)]
@@ -261,7 +291,7 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(6));
+        assert_eq!(result, Ok(6));
     }
 
     #[test]
@@ -269,6 +299,77 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file_part(
             "examples", DAY, 2,
         ));
-        assert_eq!(result, Some(6));
+        assert_eq!(result, Ok(6));
+    }
+
+    #[test]
+    fn test_walk_cycle_offsets() {
+        let input = parse(&advent_of_code::template::read_file_part(
+            "examples", DAY, 2,
+        ))
+        .unwrap();
+
+        let walk = Walk::from(&input, &node_id("22A"));
+        assert_eq!(
+            walk,
+            Walk {
+                cycle: Cycle {
+                    prefix: 1,
+                    length: 6
+                },
+                done_at: vec![3, 6],
+            }
+        );
+        assert!(walk.is_done_at(1_000_000_002));
+    }
+
+    #[test]
+    fn test_part_two_with_offset_cycles() {
+        // 11A reaches 11Z after 3 steps, then every 2; 22A reaches 22Z after 2 steps, then every 3.
+        let input = "\
+L
+
+11A = (11B, XXX)
+11B = (11C, XXX)
+11C = (11Z, XXX)
+11Z = (11Y, XXX)
+11Y = (11Z, XXX)
+22A = (22B, XXX)
+22B = (22Z, XXX)
+22Z = (22X, XXX)
+22X = (22Y, XXX)
+22Y = (22Z, XXX)
+XXX = (XXX, XXX)
+";
+        assert_eq!(part_two(input), Ok(5));
+    }
+
+    #[test]
+    fn test_part_two_never_synchronised() {
+        // 11A is on 11Z after every odd number of steps, 22A is on 22Z after every even one.
+        let input = "\
+L
+
+11A = (11Z, XXX)
+11Z = (11A, XXX)
+22A = (22B, XXX)
+22B = (22Z, XXX)
+22Z = (22B, XXX)
+XXX = (XXX, XXX)
+";
+        let error = part_two(input).unwrap_err();
+        assert_eq!(
+            error.message(),
+            "the ghosts never end on `Z` nodes at the same time"
+        );
+    }
+
+    #[test]
+    fn test_parse_error_location() {
+        let input = "LR\n\nAAA = (BBB, BBB)\nBBB = (AAA, ZZZZ)\n";
+        let error = parse(input).unwrap_err();
+
+        assert_eq!(error.message(), "node id `ZZZZ` should be 3 characters");
+        assert_eq!(error.location(), Some((4, 13)));
     }
 }
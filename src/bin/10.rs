@@ -1,5 +1,8 @@
-use std::collections::{HashMap, HashSet};
+use std::{collections::HashSet, fmt::Display};
 
+use advent_of_code::error::{At, InputError, Spanned};
+use advent_of_code::geometry::interior_points;
+use advent_of_code::graph::bfs;
 use itertools::Itertools;
 
 advent_of_code::solution!(10);
@@ -137,22 +140,10 @@ impl Input {
     }
 
     fn connections_from(&self, c: Coord) -> HashSet<Coord> {
-        let mut visited: HashSet<Coord> = HashSet::new();
-        visited.insert(c);
-
-        let mut connections: HashSet<Coord> =
-            self.get_immediate_connections(c).into_iter().collect();
-        while !connections.is_empty() {
-            visited.extend(&connections);
-
-            let new_cons: HashSet<Coord> = connections
-                .iter()
-                .flat_map(|&c| self.get_immediate_connections(c))
-                .collect();
-            connections = new_cons.difference(&visited).cloned().collect();
-        }
-
-        visited
+        bfs(c, |&c| self.get_immediate_connections(c), |_| false)
+            .into_visits()
+            .into_keys()
+            .collect()
     }
 
     fn counter_clockwise_connections_from(&self, c: Coord) -> Vec<Coord> {
@@ -191,24 +182,11 @@ impl Input {
     }
 
     fn furthest_connection_from(&self, c: Coord) -> usize {
-        let mut visited: HashSet<Coord> = HashSet::new();
-        visited.insert(c);
-
-        let mut distance = 0usize;
-        let mut connections: HashSet<Coord> =
-            self.get_immediate_connections(c).into_iter().collect();
-        while !connections.is_empty() {
-            visited.extend(&connections);
-
-            let new_cons: HashSet<Coord> = connections
-                .iter()
-                .flat_map(|&c| self.get_immediate_connections(c))
-                .collect();
-            connections = new_cons.difference(&visited).cloned().collect();
-            distance += 1;
-        }
-
-        distance
+        bfs(c, |&c| self.get_immediate_connections(c), |_| false)
+            .costs()
+            .map(|(_, distance)| distance)
+            .max()
+            .unwrap_or(0)
     }
 
     fn print(&self) -> String {
@@ -236,34 +214,6 @@ impl Input {
             })
             .join("\n")
     }
-
-    fn print_filtered_with_numbered_overlay(
-        &self,
-        filter_coords: &HashSet<Coord>,
-        overlay: &[Coord],
-    ) -> String {
-        let lookup: HashMap<&Coord, usize> =
-            overlay.iter().enumerate().map(|(i, c)| (c, i)).collect();
-        self.0
-            .iter()
-            .enumerate()
-            .map(|(y, row)| {
-                row.iter()
-                    .enumerate()
-                    .map(|(x, p)| {
-                        let c = (x as isize, y as isize);
-                        if let Some(index) = lookup.get(&c) {
-                            format!("{}", index % 10).chars().next().unwrap()
-                        } else if filter_coords.contains(&c) {
-                            point_to_char(p)
-                        } else {
-                            '·'
-                        }
-                    })
-                    .collect::<String>()
-            })
-            .join("\n")
-    }
 }
 
 #[derive(Debug)]
@@ -272,7 +222,18 @@ pub enum ParseError {
     InvalidLineLength { expected: usize, found: usize },
 }
 
-fn parse(s: &str) -> Result<Input, ParseError> {
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::UnexpectedChar(c) => write!(f, "unexpected character `{c}`"),
+            ParseError::InvalidLineLength { expected, found } => {
+                write!(f, "expected line of length {expected}, found {found}")
+            }
+        }
+    }
+}
+
+fn parse(s: &str) -> Result<Input, InputError> {
     let width = s.lines().next().expect("atleast one line").len();
     let height = s.lines().count();
 
@@ -280,7 +241,7 @@ fn parse(s: &str) -> Result<Input, ParseError> {
 
     for (index, line) in s.lines().enumerate() {
         let row = output.get_row_mut(index); // output.get_mut(index).unwrap();
-        parse_line_into_row(line, row, width)?;
+        parse_line_into_row(line, row, width).map_err(|e| e.locate(s))?;
     }
 
     Ok(output)
@@ -290,15 +251,16 @@ fn parse_line_into_row(
     line: &str,
     row: &mut Row,
     expected_length: usize,
-) -> Result<(), ParseError> {
+) -> Result<(), Spanned<ParseError>> {
     if line.len() != expected_length {
         return Err(ParseError::InvalidLineLength {
             expected: expected_length,
             found: line.len(),
-        });
+        }
+        .at(line));
     }
-    for (c, point) in std::iter::zip(line.chars(), row.iter_mut()) {
-        *point = parse_point(c)?;
+    for ((i, c), point) in std::iter::zip(line.char_indices(), row.iter_mut()) {
+        *point = parse_point(c).map_err(|e| e.at(&line[i..i + c.len_utf8()]))?;
     }
     Ok(())
 }
@@ -334,27 +296,17 @@ fn parse_point(c: char) -> Result<Option<Point>, ParseError> {
     }
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
-    let input = parse(input).expect("should parse");
+pub fn part_one(input: &str) -> Result<usize, InputError> {
+    let input = parse(input)?;
 
     let binding = input.filter(|x| *x == Some(Point::Start));
     let start = binding.first().expect("should have a start point");
 
-    Some(input.furthest_connection_from(*start))
+    Ok(input.furthest_connection_from(*start))
 }
 
-pub fn shoelace(vertices: &[Coord]) -> isize {
-    isize::abs(
-        vertices
-            .iter()
-            .circular_tuple_windows()
-            .map(|((x0, y0), (x1, y1))| x0 * y1 - (x1 * y0))
-            .sum::<isize>(),
-    ) / 2
-}
-
-pub fn part_two(input: &str) -> Option<isize> {
-    let input = parse(input).expect("should parse");
+pub fn part_two(input: &str) -> Result<isize, InputError> {
+    let input = parse(input)?;
 
     println!("input:\n{}", input.print());
 
@@ -365,12 +317,8 @@ pub fn part_two(input: &str) -> Option<isize> {
     let vertices = input.counter_clockwise_connections_from(*start);
 
     println!("loop:\n{}", input.print_filtered(&connections));
-    // println!(
-    //     "{}",
-    //     input.print_filtered_with_numbered_overlay(&connections, &vertices)
-    // );
 
-    Some(shoelace(&vertices) - (&connections.len() / 2) as isize + 1)
+    Ok(interior_points(&vertices))
 }
 
 #[cfg(test)]
@@ -380,7 +328,7 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(8));
+        assert_eq!(result, Ok(8));
     }
 
     #[test]
@@ -388,7 +336,7 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file_part(
             "examples", DAY, 2,
         ));
-        assert_eq!(result, Some(4));
+        assert_eq!(result, Ok(4));
     }
 
     #[test]
@@ -396,7 +344,7 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file_part(
             "examples", DAY, 3,
         ));
-        assert_eq!(result, Some(8));
+        assert_eq!(result, Ok(8));
     }
 
     #[test]
@@ -404,7 +352,7 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file_part(
             "examples", DAY, 4,
         ));
-        assert_eq!(result, Some(10));
+        assert_eq!(result, Ok(10));
     }
 
     #[test]
@@ -413,17 +361,18 @@ mod tests {
         let result =
             parse(&advent_of_code::template::read_file("examples", DAY)).expect("should parse");
 
-        let expected_first: Row = vec![SW, WE, SE, SW, WE]
-            .into_iter()
-            .map(|e| Some(e))
-            .collect();
+        let expected_first: Row = vec![SW, WE, SE, SW, WE].into_iter().map(Some).collect();
 
         assert_eq!(result.get_row(0), Some(&expected_first));
     }
 
     #[test]
-    fn test_shoelace() {
-        let result = shoelace(&vec![(2, 1), (5, 0), (6, 4), (4, 2), (1, 3)]);
-        assert_eq!(result, 8);
+    fn test_parse_error_location() {
+        let error = parse("..F7.\n.FJ|.\nSJ.LX\n")
+            .err()
+            .expect("should not parse");
+
+        assert_eq!(error.message(), "unexpected character `X`");
+        assert_eq!(error.location(), Some((3, 5)));
     }
 }
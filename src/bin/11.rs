@@ -1,5 +1,6 @@
 use std::collections::HashSet;
 
+use advent_of_code::geometry::manhattan;
 use itertools::Itertools;
 
 advent_of_code::solution!(11);
@@ -100,7 +101,7 @@ fn solve(input: &str, factor: usize) -> Option<usize> {
     let result = expanded_universe
         .iter()
         .tuple_combinations()
-        .map(|(a, b)| manhattan_dist(a, b))
+        .map(|(&a, &b)| manhattan(a, b))
         .sum();
 
     Some(result)
@@ -110,10 +111,6 @@ pub fn part_one(input: &str) -> Option<usize> {
     solve(input, 1usize)
 }
 
-fn manhattan_dist(a: &Coord, b: &Coord) -> usize {
-    a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
-}
-
 pub fn part_two(input: &str) -> Option<usize> {
     solve(input, 999_999usize)
 }
@@ -150,6 +147,6 @@ mod tests {
     fn test_parse_example() {
         let result = parse(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result.len(), 9);
-        assert_eq!(result.iter().next(), Some(&(3usize, 0usize)));
+        assert_eq!(result.first(), Some(&(3usize, 0usize)));
     }
 }
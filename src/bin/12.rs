@@ -0,0 +1,207 @@
+use advent_of_code::error::InputError;
+use advent_of_code::memo::{FxBuildHasher, Memo};
+use advent_of_code::{parse, verbose};
+
+advent_of_code::solution!(12);
+
+#[derive(Debug, PartialEq)]
+struct Row {
+    /// `.` operational, `#` damaged or `?` unknown.
+    springs: Vec<u8>,
+    /// Lengths of the runs of damaged springs, in order.
+    groups: Vec<usize>,
+}
+
+impl Row {
+    /// Repeats the springs `times` times separated by `?`, and the groups `times` times.
+    fn unfolded(&self, times: usize) -> Row {
+        Row {
+            springs: vec![self.springs.as_slice(); times].join(&b'?'),
+            groups: self.groups.repeat(times),
+        }
+    }
+}
+
+fn parse(input: &str) -> Result<Vec<Row>, InputError> {
+    input
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| parse_row(input, line))
+        .collect()
+}
+
+fn parse_row(input: &str, line: &str) -> Result<Row, InputError> {
+    let (springs, groups) = parse::split_once(line, ' ').map_err(|e| e.locate(input))?;
+
+    if let Some((i, c)) = springs
+        .char_indices()
+        .find(|(_, c)| !matches!(c, '.' | '#' | '?'))
+    {
+        let at = &springs[i..i + c.len_utf8()];
+        return Err(InputError::new(
+            input,
+            at,
+            format!("unexpected spring `{c}`"),
+        ));
+    }
+
+    let groups = groups
+        .split(',')
+        .map(|group| {
+            let size = parse::int(group).map_err(|e| e.locate(input))?;
+            if size == 0 {
+                let message = "expected a group of at least one spring";
+                return Err(InputError::new(input, group, message));
+            }
+            Ok(size)
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(Row {
+        springs: springs.as_bytes().to_vec(),
+        groups,
+    })
+}
+
+type Cache<'a> = Memo<(&'a [u8], &'a [usize]), u64, FxBuildHasher>;
+
+/// Number of ways to replace the unknown springs so the damaged runs match `groups`.
+fn arrangements<'a>(memo: &mut Cache<'a>, springs: &'a [u8], groups: &'a [usize]) -> u64 {
+    let Some((&group, rest)) = groups.split_first() else {
+        return u64::from(!springs.contains(&b'#'));
+    };
+    // Every group needs its springs plus a separator before the next one.
+    if springs.len() < groups.iter().sum::<usize>() + groups.len() - 1 {
+        return 0;
+    }
+
+    memo.get_or_compute((springs, groups), |memo| {
+        let operational = || springs[0] != b'#';
+        let damaged =
+            || !springs[..group].contains(&b'.') && springs.get(group).is_none_or(|&s| s != b'#');
+
+        let mut count = 0;
+        if operational() {
+            count += arrangements(memo, &springs[1..], groups);
+        }
+        if damaged() {
+            let after = springs.len().min(group + 1);
+            count += arrangements(memo, &springs[after..], rest);
+        }
+        count
+    })
+}
+
+fn total_arrangements(rows: &[Row]) -> u64 {
+    let mut memo = Cache::default();
+    let total = rows
+        .iter()
+        .map(|row| {
+            let count = arrangements(&mut memo, &row.springs, &row.groups);
+            verbose!(
+                "{} {:?}: {count}",
+                String::from_utf8_lossy(&row.springs),
+                row.groups
+            );
+            count
+        })
+        .sum();
+    verbose!("cache: {}", memo.stats());
+    total
+}
+
+pub fn part_one(input: &str) -> Result<u64, InputError> {
+    let rows = parse(input)?;
+
+    Ok(total_arrangements(&rows))
+}
+
+pub fn part_two(input: &str) -> Result<u64, InputError> {
+    let rows: Vec<Row> = parse(input)?.iter().map(|row| row.unfolded(5)).collect();
+
+    Ok(total_arrangements(&rows))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example_row(index: usize) -> String {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        input.lines().nth(index).unwrap().to_string()
+    }
+
+    #[test]
+    fn test_parse_example() {
+        let rows = parse(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+
+        assert_eq!(rows.len(), 6);
+        assert_eq!(
+            rows[0],
+            Row {
+                springs: b"???.###".to_vec(),
+                groups: vec![1, 1, 3],
+            }
+        );
+    }
+
+    #[test]
+    fn test_part_one_rows() {
+        let expected = [1, 4, 1, 1, 4, 10];
+        for (index, count) in expected.into_iter().enumerate() {
+            assert_eq!(part_one(&example_row(index)), Ok(count), "row {index}");
+        }
+    }
+
+    #[test]
+    fn test_part_two_rows() {
+        let expected = [1, 16384, 1, 16, 2500, 506250];
+        for (index, count) in expected.into_iter().enumerate() {
+            assert_eq!(part_two(&example_row(index)), Ok(count), "row {index}");
+        }
+    }
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Ok(21));
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Ok(525152));
+    }
+
+    #[test]
+    fn test_unfolded() {
+        let row = Row {
+            springs: b".#".to_vec(),
+            groups: vec![1],
+        };
+
+        assert_eq!(
+            row.unfolded(5),
+            Row {
+                springs: b".#?.#?.#?.#?.#".to_vec(),
+                groups: vec![1; 5],
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_error_location() {
+        let error = parse("???.### 1,1,3\n.??..?x...?##. 1,1,3\n").unwrap_err();
+
+        assert_eq!(error.message(), "unexpected spring `x`");
+        assert_eq!(error.location(), Some((2, 7)));
+    }
+
+    #[test]
+    fn test_empty_group() {
+        let error = parse("# 1\n. 1,0\n").unwrap_err();
+
+        assert_eq!(error.message(), "expected a group of at least one spring");
+        assert_eq!(error.location(), Some((2, 5)));
+    }
+}
@@ -0,0 +1,168 @@
+use advent_of_code::error::InputError;
+use advent_of_code::grid::Grid;
+use advent_of_code::parse;
+
+advent_of_code::solution!(13);
+
+/// A pattern as bitmasks (`#` set) per row and per column; patterns are at most 64 wide and high.
+#[derive(Debug, PartialEq)]
+struct Pattern {
+    rows: Vec<u64>,
+    columns: Vec<u64>,
+}
+
+impl Pattern {
+    fn from_grid(grid: &Grid<bool>) -> Pattern {
+        let mut rows = vec![0; grid.height()];
+        let mut columns = vec![0; grid.width()];
+        for ((x, y), _) in grid.iter().filter(|(_, &rock)| rock) {
+            rows[y as usize] |= 1 << x;
+            columns[x as usize] |= 1 << y;
+        }
+        Pattern { rows, columns }
+    }
+
+    /// All lines where the mirrored halves differ in exactly `smudges` cells.
+    fn reflections(&self, smudges: u32) -> Vec<Reflection> {
+        let vertical = mirror_lines(&self.columns, smudges).map(Reflection::Vertical);
+        let horizontal = mirror_lines(&self.rows, smudges).map(Reflection::Horizontal);
+        vertical.chain(horizontal).collect()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Reflection {
+    /// Between this many columns on the left and the rest.
+    Vertical(usize),
+    /// Between this many rows above and the rest.
+    Horizontal(usize),
+}
+
+impl Reflection {
+    fn score(&self) -> usize {
+        match self {
+            Reflection::Vertical(columns) => *columns,
+            Reflection::Horizontal(rows) => 100 * rows,
+        }
+    }
+}
+
+/// Positions between `lines` where the mirrored lines differ in exactly `smudges` bits in total.
+fn mirror_lines(lines: &[u64], smudges: u32) -> impl Iterator<Item = usize> + '_ {
+    (1..lines.len()).filter(move |&at| {
+        let (before, after) = lines.split_at(at);
+        let differences: u32 = std::iter::zip(before.iter().rev(), after)
+            .map(|(a, b)| (a ^ b).count_ones())
+            .sum();
+        differences == smudges
+    })
+}
+
+fn parse(input: &str) -> Result<Vec<Pattern>, InputError> {
+    parse::sections(input)
+        .map(|section| {
+            let grid = Grid::parse_spanned(section, |c| match c {
+                '#' => Some(true),
+                '.' => Some(false),
+                _ => None,
+            })
+            .map_err(|e| e.locate(input))?;
+            if grid.width() > 64 || grid.height() > 64 {
+                return Err(InputError::new(
+                    input,
+                    section,
+                    "patterns should be at most 64 wide and high",
+                ));
+            }
+            Ok(Pattern::from_grid(&grid))
+        })
+        .collect()
+}
+
+fn summarize(patterns: &[Pattern], smudges: u32) -> usize {
+    patterns
+        .iter()
+        .flat_map(|pattern| pattern.reflections(smudges))
+        .map(|reflection| reflection.score())
+        .sum()
+}
+
+pub fn part_one(input: &str) -> Result<usize, InputError> {
+    Ok(summarize(&parse(input)?, 0))
+}
+
+pub fn part_two(input: &str) -> Result<usize, InputError> {
+    Ok(summarize(&parse(input)?, 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_example() {
+        let patterns = parse(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+
+        assert_eq!(patterns.len(), 2);
+        assert_eq!(patterns[0].rows.len(), 7);
+        assert_eq!(patterns[0].columns.len(), 9);
+        assert_eq!(patterns[0].rows[0], 0b0_1100_1101);
+        assert_eq!(patterns[0].columns[0], 0b100_1101);
+    }
+
+    #[test]
+    fn test_example_reflections() {
+        let patterns = parse(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+
+        assert_eq!(patterns[0].reflections(0), vec![Reflection::Vertical(5)]);
+        assert_eq!(patterns[1].reflections(0), vec![Reflection::Horizontal(4)]);
+        assert_eq!(patterns[0].reflections(1), vec![Reflection::Horizontal(3)]);
+        assert_eq!(patterns[1].reflections(1), vec![Reflection::Horizontal(1)]);
+    }
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Ok(405));
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Ok(400));
+    }
+
+    #[test]
+    fn test_both_candidates() {
+        let input = "#..#\n.##.\n.##.\n#..#\n";
+        let patterns = parse(input).unwrap();
+
+        assert_eq!(
+            patterns[0].reflections(0),
+            vec![Reflection::Vertical(2), Reflection::Horizontal(2)]
+        );
+        assert_eq!(part_one(input), Ok(202));
+    }
+
+    #[test]
+    fn test_both_candidates_with_smudge() {
+        // Fixing the smudge at the top left makes the pattern symmetric in both directions.
+        let input = "...#\n.##.\n.##.\n#..#\n";
+        let patterns = parse(input).unwrap();
+
+        assert_eq!(patterns[0].reflections(0), vec![]);
+        assert_eq!(
+            patterns[0].reflections(1),
+            vec![Reflection::Vertical(2), Reflection::Horizontal(2)]
+        );
+        assert_eq!(part_two(input), Ok(202));
+    }
+
+    #[test]
+    fn test_parse_error_location() {
+        let error = parse("#.\n..\n\n#.\n.x\n").unwrap_err();
+
+        assert_eq!(error.message(), "unexpected character `x`");
+        assert_eq!(error.location(), Some((5, 2)));
+    }
+}
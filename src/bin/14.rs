@@ -0,0 +1,233 @@
+use advent_of_code::cycle;
+use advent_of_code::error::InputError;
+use advent_of_code::geometry::Direction;
+use advent_of_code::grid::{Grid, GridPoint};
+use advent_of_code::verbose;
+
+advent_of_code::solution!(14);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Cell {
+    Round,
+    Cube,
+    Empty,
+}
+
+impl Cell {
+    fn from_char(c: char) -> Option<Cell> {
+        match c {
+            'O' => Some(Cell::Round),
+            '#' => Some(Cell::Cube),
+            '.' => Some(Cell::Empty),
+            _ => None,
+        }
+    }
+
+    fn to_char(self) -> char {
+        match self {
+            Cell::Round => 'O',
+            Cell::Cube => '#',
+            Cell::Empty => '.',
+        }
+    }
+}
+
+type Platform = Grid<Cell>;
+
+fn parse(input: &str) -> Result<Platform, InputError> {
+    Grid::parse(input, Cell::from_char)
+}
+
+fn render(platform: &Platform) -> String {
+    platform.render(|_, cell| cell.to_char())
+}
+
+/// The lines along which rocks roll towards `direction`, each starting at the edge they roll to.
+fn lanes(platform: &Platform, direction: Direction) -> Vec<Vec<GridPoint>> {
+    let (width, height) = (platform.width() as isize, platform.height() as isize);
+    match direction {
+        Direction::North => (0..width)
+            .map(|x| (0..height).map(|y| (x, y)).collect())
+            .collect(),
+        Direction::South => (0..width)
+            .map(|x| (0..height).rev().map(|y| (x, y)).collect())
+            .collect(),
+        Direction::West => (0..height)
+            .map(|y| (0..width).map(|x| (x, y)).collect())
+            .collect(),
+        Direction::East => (0..height)
+            .map(|y| (0..width).rev().map(|x| (x, y)).collect())
+            .collect(),
+    }
+}
+
+/// Rolls all round rocks as far as possible towards `direction`.
+fn tilt(platform: &mut Platform, direction: Direction) {
+    for lane in lanes(platform, direction) {
+        let mut free = 0;
+        for (i, &point) in lane.iter().enumerate() {
+            match platform[point] {
+                Cell::Cube => free = i + 1,
+                Cell::Round => {
+                    platform[point] = Cell::Empty;
+                    platform[lane[free]] = Cell::Round;
+                    free += 1;
+                }
+                Cell::Empty => {}
+            }
+        }
+    }
+}
+
+/// Tilts north, west, south and then east.
+fn spin(platform: &Platform) -> Platform {
+    let mut platform = platform.clone();
+    for direction in [
+        Direction::North,
+        Direction::West,
+        Direction::South,
+        Direction::East,
+    ] {
+        tilt(&mut platform, direction);
+    }
+    platform
+}
+
+/// Load on the north support beams: each round rock counts its distance to the south edge.
+fn north_load(platform: &Platform) -> usize {
+    platform
+        .iter()
+        .filter(|(_, &cell)| cell == Cell::Round)
+        .map(|((_, y), _)| platform.height() - y as usize)
+        .sum()
+}
+
+pub fn part_one(input: &str) -> Result<usize, InputError> {
+    let mut platform = parse(input)?;
+    tilt(&mut platform, Direction::North);
+
+    Ok(north_load(&platform))
+}
+
+pub fn part_two(input: &str) -> Result<usize, InputError> {
+    let platform = parse(input)?;
+
+    let history = cycle::find(platform, spin);
+    let cycle = history.cycle;
+    verbose!(
+        "spins repeat after {} spins, every {} spins",
+        cycle.prefix,
+        cycle.length
+    );
+
+    let last = history.state_at(1_000_000_000);
+    verbose!("after 1000000000 spins:\n{}", render(last));
+
+    Ok(north_load(last))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Platform {
+        parse(&advent_of_code::template::read_file("examples", DAY)).unwrap()
+    }
+
+    #[test]
+    fn test_tilt_north() {
+        let mut platform = example();
+        tilt(&mut platform, Direction::North);
+
+        assert_eq!(
+            render(&platform),
+            "\
+OOOO.#.O..
+OO..#....#
+OO..O##..O
+O..#.OO...
+........#.
+..#....#.#
+..O..#.O.O
+..O.......
+#....###..
+#....#...."
+        );
+    }
+
+    #[test]
+    fn test_spins() {
+        let after_one = spin(&example());
+        assert_eq!(
+            render(&after_one),
+            "\
+.....#....
+....#...O#
+...OO##...
+.OO#......
+.....OOO#.
+.O#...O#.#
+....O#....
+......OOOO
+#...O###..
+#..OO#...."
+        );
+
+        let after_two = spin(&after_one);
+        assert_eq!(
+            render(&after_two),
+            "\
+.....#....
+....#...O#
+.....##...
+..O#......
+.....OOO#.
+.O#...O#.#
+....O#...O
+.......OOO
+#..OO###..
+#.OOO#...O"
+        );
+
+        let after_three = spin(&after_two);
+        assert_eq!(
+            render(&after_three),
+            "\
+.....#....
+....#...O#
+.....##...
+..O#......
+.....OOO#.
+.O#...O#.#
+....O#...O
+.......OOO
+#...O###.O
+#.OOO#...O"
+        );
+    }
+
+    #[test]
+    fn test_spin_cycle() {
+        let history = cycle::find(example(), spin);
+
+        assert_eq!(
+            history.cycle,
+            cycle::Cycle {
+                prefix: 3,
+                length: 7
+            }
+        );
+    }
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Ok(136));
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Ok(64));
+    }
+}
@@ -1,4 +1,6 @@
-use std::{collections::VecDeque, num::ParseIntError};
+use std::{collections::VecDeque, fmt::Display, num::ParseIntError};
+
+use advent_of_code::error::{At, InputError};
 
 advent_of_code::solution!(15);
 
@@ -40,32 +42,42 @@ enum ParseError {
     InvalidInt(ParseIntError),
 }
 
-fn parse(s: &str) -> Result<Vec<Input>, ParseError> {
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::InvalidInput(c) => write!(f, "invalid operation `{c}`"),
+            ParseError::NoSplit => write!(f, "expected `-` or `=`"),
+            ParseError::InvalidInt(e) => write!(f, "invalid focal length: {e}"),
+        }
+    }
+}
+
+fn parse(s: &str) -> Result<Vec<Input<'_>>, InputError> {
     s.split(',')
         .map(|e| {
             let split_index = e
-                .find(|c| c == '-' || c == '=')
-                .ok_or(ParseError::NoSplit)?;
+                .find(['-', '='])
+                .ok_or_else(|| ParseError::NoSplit.at(e).locate(s))?;
             let (label, rest) = e
-                .split_once(|c| c == '-' || c == '=')
-                .ok_or(ParseError::NoSplit)?;
+                .split_once(['-', '='])
+                .ok_or_else(|| ParseError::NoSplit.at(e).locate(s))?;
             match e.chars().nth(split_index).unwrap() {
                 '-' => Ok(Input::RemoveLens(label)),
                 '=' => {
+                    let rest = rest.trim();
                     let focal = rest
-                        .trim()
                         .parse::<usize>()
-                        .map_err(ParseError::InvalidInt)?;
+                        .map_err(|err| ParseError::InvalidInt(err).at(rest).locate(s))?;
                     Ok(Input::AddLens(label, focal))
                 }
-                invalid => Err(ParseError::InvalidInput(invalid)),
+                invalid => Err(ParseError::InvalidInput(invalid).at(e).locate(s)),
             }
         })
         .collect()
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let inputs = parse(input).expect("should parse");
+pub fn part_two(input: &str) -> Result<usize, InputError> {
+    let inputs = parse(input)?;
 
     let mut boxes: Vec<Box> = (0..256).map(|_| VecDeque::new()).collect();
 
@@ -90,21 +102,19 @@ pub fn part_two(input: &str) -> Option<usize> {
         };
     }
 
-    Some(
-        boxes
-            .iter()
-            .enumerate()
-            .map(|(box_index, lenses)| {
-                lenses
-                    .iter()
-                    .enumerate()
-                    .map(|(lens_index, (_, focal_length))| {
-                        (box_index + 1) * (lens_index + 1) * focal_length
-                    })
-                    .sum::<usize>()
-            })
-            .sum::<usize>(),
-    )
+    Ok(boxes
+        .iter()
+        .enumerate()
+        .map(|(box_index, lenses)| {
+            lenses
+                .iter()
+                .enumerate()
+                .map(|(lens_index, (_, focal_length))| {
+                    (box_index + 1) * (lens_index + 1) * focal_length
+                })
+                .sum::<usize>()
+        })
+        .sum::<usize>())
 }
 
 #[cfg(test)]
@@ -120,7 +130,15 @@ mod tests {
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(145));
+        assert_eq!(result, Ok(145));
+    }
+
+    #[test]
+    fn test_parse_error_location() {
+        let error = parse("rn=1,cm-,qp=x3").unwrap_err();
+
+        assert!(error.message().starts_with("invalid focal length"));
+        assert_eq!(error.location(), Some((1, 13)));
     }
 
     #[test]
@@ -0,0 +1,193 @@
+use std::thread;
+
+use advent_of_code::error::InputError;
+use advent_of_code::geometry::{step, Direction};
+use advent_of_code::graph::{bfs_in, Dense, NodeMap};
+use advent_of_code::grid::{Grid, GridPoint};
+
+advent_of_code::solution!(16);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tile {
+    Empty,
+    /// `/`
+    MirrorUp,
+    /// `\`
+    MirrorDown,
+    /// `|`
+    SplitVertical,
+    /// `-`
+    SplitHorizontal,
+}
+
+impl Tile {
+    fn from_char(c: char) -> Option<Tile> {
+        match c {
+            '.' => Some(Tile::Empty),
+            '/' => Some(Tile::MirrorUp),
+            '\\' => Some(Tile::MirrorDown),
+            '|' => Some(Tile::SplitVertical),
+            '-' => Some(Tile::SplitHorizontal),
+            _ => None,
+        }
+    }
+
+    /// The directions a beam travelling in `direction` leaves this tile in.
+    fn outgoing(self, direction: Direction) -> impl Iterator<Item = Direction> {
+        use Direction::*;
+        let (first, second) = match (self, direction) {
+            (Tile::MirrorUp, North | South) => (direction.turn_right(), None),
+            (Tile::MirrorUp, East | West) => (direction.turn_left(), None),
+            (Tile::MirrorDown, North | South) => (direction.turn_left(), None),
+            (Tile::MirrorDown, East | West) => (direction.turn_right(), None),
+            (Tile::SplitVertical, East | West) => (North, Some(South)),
+            (Tile::SplitHorizontal, North | South) => (East, Some(West)),
+            _ => (direction, None),
+        };
+        std::iter::once(first).chain(second)
+    }
+}
+
+type Contraption = Grid<Tile>;
+
+/// A beam entering a tile while travelling in a direction.
+type Beam = (GridPoint, Direction);
+
+fn parse(input: &str) -> Result<Contraption, InputError> {
+    let contraption = Grid::parse(input, Tile::from_char)?;
+    if contraption.is_empty() {
+        return Err(InputError::unlocated("expected a contraption"));
+    }
+    Ok(contraption)
+}
+
+/// Number of tiles a beam entering as `start` passes through, including splits and loops.
+fn energized(contraption: &Contraption, start: Beam) -> usize {
+    let index = |&(point, direction): &Beam| {
+        contraption.index_of(point).expect("beams stay inside") * 4 + direction as usize
+    };
+    let visits = Dense::new(contraption.len() * 4, index);
+
+    let search = bfs_in(
+        visits,
+        start,
+        |&(point, direction)| {
+            contraption[point]
+                .outgoing(direction)
+                .map(move |out| (step(point, out, 1), out))
+                .filter(|&(next, _)| contraption.contains(next))
+        },
+        |_| false,
+    );
+
+    let visits = search.into_visits();
+    contraption
+        .points()
+        .filter(|&point| {
+            Direction::ALL
+                .iter()
+                .any(|&direction| visits.get(&(point, direction)).is_some())
+        })
+        .count()
+}
+
+/// Every beam entering from an edge, heading inwards.
+fn edge_beams(contraption: &Contraption) -> Vec<Beam> {
+    let (width, height) = (contraption.width() as isize, contraption.height() as isize);
+    let columns = (0..width).flat_map(|x| {
+        [
+            ((x, 0), Direction::South),
+            ((x, height - 1), Direction::North),
+        ]
+    });
+    let rows =
+        (0..height).flat_map(|y| [((0, y), Direction::East), ((width - 1, y), Direction::West)]);
+    columns.chain(rows).collect()
+}
+
+pub fn part_one(input: &str) -> Result<usize, InputError> {
+    let contraption = parse(input)?;
+
+    Ok(energized(&contraption, ((0, 0), Direction::East)))
+}
+
+pub fn part_two(input: &str) -> Result<usize, InputError> {
+    let contraption = parse(input)?;
+    let beams = edge_beams(&contraption);
+
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = beams.len().div_ceil(threads).max(1);
+
+    let best = thread::scope(|scope| {
+        let workers: Vec<_> = beams
+            .chunks(chunk_size)
+            .map(|chunk| {
+                let contraption = &contraption;
+                scope.spawn(move || chunk.iter().map(|&beam| energized(contraption, beam)).max())
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .filter_map(|worker| worker.join().expect("worker should not panic"))
+            .max()
+    });
+
+    Ok(best.unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_outgoing() {
+        let outgoing = |tile: Tile, direction| tile.outgoing(direction).collect::<HashSet<_>>();
+
+        assert_eq!(
+            outgoing(Tile::MirrorUp, Direction::East),
+            HashSet::from([Direction::North])
+        );
+        assert_eq!(
+            outgoing(Tile::MirrorDown, Direction::East),
+            HashSet::from([Direction::South])
+        );
+        assert_eq!(
+            outgoing(Tile::SplitVertical, Direction::West),
+            HashSet::from([Direction::North, Direction::South])
+        );
+        assert_eq!(
+            outgoing(Tile::SplitVertical, Direction::North),
+            HashSet::from([Direction::North])
+        );
+    }
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Ok(46));
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Ok(51));
+    }
+
+    #[test]
+    fn test_splitter_loop_terminates() {
+        // Both halves of the split beam circle between the mirrors forever.
+        let input = "\\/.\\\n-|..\n.\\./\n";
+
+        assert_eq!(part_one(input), Ok(10));
+    }
+
+    #[test]
+    fn test_empty_input() {
+        for result in [part_one(""), part_two("\n")] {
+            assert_eq!(result.unwrap_err().message(), "expected a contraption");
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+
+use advent_of_code::error::InputError;
+use advent_of_code::geometry::{step, Direction};
+use advent_of_code::graph::dijkstra;
+use advent_of_code::grid::{Grid, GridPoint};
+use advent_of_code::verbose;
+
+advent_of_code::solution!(17);
+
+type City = Grid<u32>;
+
+/// How far a crucible has to and may move in a straight line before turning.
+#[derive(Debug, Clone, Copy)]
+struct Crucible {
+    min_run: u8,
+    max_run: u8,
+}
+
+const CRUCIBLE: Crucible = Crucible {
+    min_run: 0,
+    max_run: 3,
+};
+
+const ULTRA_CRUCIBLE: Crucible = Crucible {
+    min_run: 4,
+    max_run: 10,
+};
+
+/// Position, the direction it was entered in (none at the start) and the straight run so far.
+type State = (GridPoint, Option<Direction>, u8);
+
+fn parse(input: &str) -> Result<City, InputError> {
+    Grid::parse(input, |c| c.to_digit(10))
+}
+
+/// The least heat loss from the top left to the bottom right block, with the path taken.
+fn least_heat_loss(city: &City, crucible: Crucible) -> Option<(u32, Vec<State>)> {
+    let target = (city.width() as isize - 1, city.height() as isize - 1);
+
+    let search = dijkstra(
+        ((0, 0), None, 0),
+        |&(point, entered, run): &State| {
+            Direction::ALL
+                .into_iter()
+                .filter(move |&direction| match entered {
+                    None => true,
+                    Some(entered) if direction == entered => run < crucible.max_run,
+                    Some(entered) => direction != entered.opposite() && run >= crucible.min_run,
+                })
+                .map(move |direction| {
+                    let run = if entered == Some(direction) {
+                        run + 1
+                    } else {
+                        1
+                    };
+                    (step(point, direction, 1), Some(direction), run)
+                })
+                .filter(|&(next, _, _)| city.contains(next))
+                .map(|state| (state, city[state.0]))
+        },
+        |&(point, _, run)| point == target && run >= crucible.min_run,
+    );
+
+    Some((search.goal_cost()?, search.path()?))
+}
+
+/// Draws the path over the city, with arrows for the direction of each step.
+fn render_path(city: &City, path: &[State]) -> String {
+    let arrows: HashMap<GridPoint, char> = path
+        .iter()
+        .filter_map(|&(point, direction, _)| {
+            let arrow = match direction? {
+                Direction::North => '^',
+                Direction::East => '>',
+                Direction::South => 'v',
+                Direction::West => '<',
+            };
+            Some((point, arrow))
+        })
+        .collect();
+
+    city.render(|point, &loss| {
+        arrows
+            .get(&point)
+            .copied()
+            .unwrap_or_else(|| char::from_digit(loss, 10).unwrap_or('?'))
+    })
+}
+
+fn solve(input: &str, crucible: Crucible) -> Result<u32, InputError> {
+    let city = parse(input)?;
+
+    let (loss, path) = least_heat_loss(&city, crucible)
+        .ok_or_else(|| InputError::unlocated("no path reaches the bottom right block"))?;
+    verbose!(
+        "path of {} blocks:\n{}",
+        path.len() - 1,
+        render_path(&city, &path)
+    );
+
+    Ok(loss)
+}
+
+pub fn part_one(input: &str) -> Result<u32, InputError> {
+    solve(input, CRUCIBLE)
+}
+
+pub fn part_two(input: &str) -> Result<u32, InputError> {
+    solve(input, ULTRA_CRUCIBLE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Ok(102));
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Ok(94));
+    }
+
+    #[test]
+    fn test_part_two_example_two() {
+        let result = part_two(&advent_of_code::template::read_file_part(
+            "examples", DAY, 2,
+        ));
+        assert_eq!(result, Ok(71));
+    }
+
+    #[test]
+    fn test_path_respects_runs() {
+        let input = advent_of_code::template::read_file_part("examples", DAY, 2);
+        let city = parse(&input).unwrap();
+        let (_, path) = least_heat_loss(&city, ULTRA_CRUCIBLE).unwrap();
+
+        assert_eq!(
+            render_path(&city, &path),
+            "\
+1>>>>>>>1111
+9999999v9991
+9999999v9991
+9999999v9991
+9999999v>>>>"
+        );
+    }
+}
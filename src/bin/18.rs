@@ -0,0 +1,175 @@
+use std::fmt::Display;
+
+use advent_of_code::error::{At, InputError, Spanned};
+use advent_of_code::geometry::{
+    boundary_points, interior_points, polygon_from_instructions, Direction,
+};
+use advent_of_code::parse;
+
+advent_of_code::solution!(18);
+
+#[derive(Debug, PartialEq, Eq)]
+struct Instruction<'a> {
+    direction: Direction,
+    length: i64,
+    /// The six hex digits of the colour, without the `#`.
+    colour: &'a str,
+}
+
+impl Instruction<'_> {
+    /// Reads the real instruction from the colour: five hex digits of length, then the direction.
+    fn decoded(&self) -> Result<(Direction, i64), Spanned<ParseError>> {
+        let (length, direction) = self.colour.split_at(5);
+        let length = i64::from_str_radix(length, 16)
+            .map_err(|_| ParseError::InvalidColour.at(self.colour))?;
+        let direction = match direction {
+            "0" => Direction::East,
+            "1" => Direction::South,
+            "2" => Direction::West,
+            "3" => Direction::North,
+            _ => return Err(ParseError::InvalidDirection.at(direction)),
+        };
+        Ok((direction, length))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum ParseError {
+    InvalidDirection,
+    InvalidColour,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::InvalidDirection => write!(f, "invalid direction"),
+            ParseError::InvalidColour => write!(f, "expected a colour like `(#70c710)`"),
+        }
+    }
+}
+
+fn parse(input: &str) -> Result<Vec<Instruction<'_>>, InputError> {
+    input
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| parse_instruction(line, input))
+        .collect()
+}
+
+fn parse_instruction<'a>(line: &'a str, input: &str) -> Result<Instruction<'a>, InputError> {
+    let mut fields = line.split_whitespace();
+    let (direction_str, length_str, colour_str) =
+        match (fields.next(), fields.next(), fields.next()) {
+            (Some(d), Some(l), Some(c)) => (d, l, c),
+            _ => {
+                return Err(InputError::new(
+                    input,
+                    line,
+                    "expected direction, length and colour",
+                ))
+            }
+        };
+
+    let direction = match direction_str {
+        "U" => Direction::North,
+        "R" => Direction::East,
+        "D" => Direction::South,
+        "L" => Direction::West,
+        _ => return Err(ParseError::InvalidDirection.at(direction_str).locate(input)),
+    };
+    let length = parse::int(length_str).map_err(|e| e.locate(input))?;
+
+    let colour = colour_str
+        .strip_prefix("(#")
+        .and_then(|c| c.strip_suffix(')'))
+        .filter(|c| c.len() == 6 && c.chars().all(|c| c.is_ascii_hexdigit()))
+        .ok_or_else(|| ParseError::InvalidColour.at(colour_str).locate(input))?;
+
+    Ok(Instruction {
+        direction,
+        length,
+        colour,
+    })
+}
+
+/// Cubic metres of lava the dug out lagoon holds: the trench itself plus everything inside it.
+fn lagoon_size(instructions: impl IntoIterator<Item = (Direction, i64)>) -> i64 {
+    let vertices = polygon_from_instructions((0, 0), instructions);
+    boundary_points(&vertices) + interior_points(&vertices)
+}
+
+pub fn part_one(input: &str) -> Result<i64, InputError> {
+    let instructions = parse(input)?;
+
+    Ok(lagoon_size(
+        instructions.iter().map(|i| (i.direction, i.length)),
+    ))
+}
+
+pub fn part_two(input: &str) -> Result<i64, InputError> {
+    let instructions = parse(input)?;
+
+    let decoded = instructions
+        .iter()
+        .map(|i| i.decoded().map_err(|e| e.locate(input)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(lagoon_size(decoded))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_example() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let instructions = parse(&input).unwrap();
+
+        assert_eq!(instructions.len(), 14);
+        assert_eq!(
+            instructions[0],
+            Instruction {
+                direction: Direction::East,
+                length: 6,
+                colour: "70c710",
+            }
+        );
+    }
+
+    #[test]
+    fn test_decoded() {
+        let instruction = Instruction {
+            direction: Direction::East,
+            length: 6,
+            colour: "70c710",
+        };
+        assert_eq!(instruction.decoded(), Ok((Direction::East, 461937)));
+
+        let instruction = Instruction {
+            colour: "caa173",
+            ..instruction
+        };
+        assert_eq!(instruction.decoded(), Ok((Direction::North, 829975)));
+    }
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Ok(62));
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Ok(952408144115));
+    }
+
+    #[test]
+    fn test_parse_error_location() {
+        let error = parse("R 6 (#70c710)\nD 5 (#0dc57)\n").unwrap_err();
+
+        assert_eq!(error.message(), "expected a colour like `(#70c710)`");
+        assert_eq!(error.location(), Some((2, 5)));
+    }
+}
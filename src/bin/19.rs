@@ -1,4 +1,7 @@
-use std::{collections::HashMap, num::ParseIntError};
+use std::{collections::HashMap, fmt::Display, num::ParseIntError};
+
+use advent_of_code::error::{At, InputError, Spanned};
+use advent_of_code::interval::{Cuboid, Interval};
 
 advent_of_code::solution!(19);
 
@@ -95,9 +98,24 @@ enum ParseError {
     Unmatched(char),
     InvalidInt(ParseIntError),
     ExpectedDefaultDestination,
+    UnknownQuality,
+    UnknownCondition,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::Expected(c) => write!(f, "expected `{c}`"),
+            ParseError::Unmatched(c) => write!(f, "unmatched `{c}`"),
+            ParseError::InvalidInt(e) => write!(f, "invalid rating: {e}"),
+            ParseError::ExpectedDefaultDestination => write!(f, "expected a default destination"),
+            ParseError::UnknownQuality => write!(f, "expected a rating `x`, `m`, `a` or `s`"),
+            ParseError::UnknownCondition => write!(f, "expected `<` or `>`"),
+        }
+    }
 }
 
-fn parse(s: &str) -> Result<Input, ParseError> {
+fn parse(s: &str) -> Result<Input<'_>, InputError> {
     let mut workflows: HashMap<_, _> = HashMap::new();
     let mut parts = Vec::new();
     let mut doing_parts = false;
@@ -107,10 +125,15 @@ fn parse(s: &str) -> Result<Input, ParseError> {
             continue;
         }
         if !doing_parts {
-            let workflow = parse_workflow(line)?;
+            let workflow = parse_workflow(line).map_err(|e| {
+                let name = line.split('{').next().unwrap_or(line);
+                e.locate(s)
+                    .context(format!("while parsing workflow `{name}`"))
+            })?;
             workflows.insert(workflow.name, workflow);
         } else {
-            let part = parse_part(line)?;
+            let part =
+                parse_part(line).map_err(|e| e.locate(s).context("while parsing part ratings"))?;
             parts.push(part);
         }
     }
@@ -118,59 +141,79 @@ fn parse(s: &str) -> Result<Input, ParseError> {
     Ok(Input { workflows, parts })
 }
 
-fn parse_part_quality(input: &str, quality: char) -> Result<Value, ParseError> {
-    let (actual_qual, value_str) = input.split_once('=').ok_or(ParseError::Expected('='))?;
+fn parse_part_quality(input: &str, quality: char) -> Result<Value, Spanned<ParseError>> {
+    let (actual_qual, value_str) = input
+        .split_once('=')
+        .ok_or(ParseError::Expected('=').at(input))?;
 
-    if actual_qual.len() > 1 || actual_qual.chars().next().unwrap() != quality {
-        return Err(ParseError::Expected(quality));
+    if actual_qual.len() > 1 || !actual_qual.starts_with(quality) {
+        return Err(ParseError::Expected(quality).at(actual_qual));
     }
 
-    let value = value_str.parse::<Value>().map_err(ParseError::InvalidInt)?;
+    let value = value_str
+        .parse::<Value>()
+        .map_err(|e| ParseError::InvalidInt(e).at(value_str))?;
 
     Ok(value)
 }
 
-fn parse_part(line: &str) -> Result<Part, ParseError> {
-    let inner = line.strip_prefix('{').ok_or(ParseError::Expected('{'))?;
-    let inner = inner.strip_suffix('}').ok_or(ParseError::Unmatched('{'))?;
+fn parse_part(line: &str) -> Result<Part, Spanned<ParseError>> {
+    let end = &line[line.len()..];
+    let inner = line
+        .strip_prefix('{')
+        .ok_or(ParseError::Expected('{').at(line))?;
+    let inner = inner
+        .strip_suffix('}')
+        .ok_or(ParseError::Unmatched('{').at(&line[..1]))?;
 
     let mut qualities = inner.split(',');
-    let x_str = qualities.next().ok_or(ParseError::Expected(','))?;
+    let x_str = qualities.next().ok_or(ParseError::Expected(',').at(end))?;
     let x = parse_part_quality(x_str, 'x')?;
 
-    let m_str = qualities.next().ok_or(ParseError::Expected(','))?;
+    let m_str = qualities.next().ok_or(ParseError::Expected(',').at(end))?;
     let m = parse_part_quality(m_str, 'm')?;
 
-    let a_str = qualities.next().ok_or(ParseError::Expected(','))?;
+    let a_str = qualities.next().ok_or(ParseError::Expected(',').at(end))?;
     let a = parse_part_quality(a_str, 'a')?;
 
-    let s_str = qualities.next().ok_or(ParseError::Expected(','))?;
+    let s_str = qualities.next().ok_or(ParseError::Expected(',').at(end))?;
     let s = parse_part_quality(s_str, 's')?;
 
     Ok(Part { x, m, a, s })
 }
 
-fn parse_rule(input: &str) -> Result<Rule, ParseError> {
-    let (qual_cond_str, destination) = input.split_once(':').ok_or(ParseError::Expected(':'))?;
-
-    let mut qual_cond_iter = qual_cond_str.chars();
-
-    let qual = match qual_cond_iter.next().ok_or(ParseError::Expected('q'))? {
-        'x' => Ok(Quality::X),
-        'm' => Ok(Quality::M),
-        'a' => Ok(Quality::A),
-        's' => Ok(Quality::S),
-        _ => Err(ParseError::Expected('q')),
-    }?;
-
-    let cond = match qual_cond_iter.next().ok_or(ParseError::Expected('c'))? {
-        '>' => Ok(Condition::GreaterThan),
-        '<' => Ok(Condition::LessThan),
-        _ => Err(ParseError::Expected('c')),
-    }?;
-
-    let value_str: String = qual_cond_iter.collect();
-    let val = value_str.parse::<Value>().map_err(ParseError::InvalidInt)?;
+fn parse_rule(input: &str) -> Result<Rule<'_>, Spanned<ParseError>> {
+    let (qual_cond_str, destination) = input
+        .split_once(':')
+        .ok_or(ParseError::Expected(':').at(input))?;
+
+    let mut chars = qual_cond_str.char_indices();
+    // The span of a single character, or the empty span after the last one.
+    let span = |next: Option<(usize, char)>| match next {
+        Some((i, c)) => &qual_cond_str[i..i + c.len_utf8()],
+        None => &qual_cond_str[qual_cond_str.len()..],
+    };
+
+    let next = chars.next();
+    let qual = match next {
+        Some((_, 'x')) => Quality::X,
+        Some((_, 'm')) => Quality::M,
+        Some((_, 'a')) => Quality::A,
+        Some((_, 's')) => Quality::S,
+        _ => return Err(ParseError::UnknownQuality.at(span(next))),
+    };
+
+    let next = chars.next();
+    let cond = match next {
+        Some((_, '>')) => Condition::GreaterThan,
+        Some((_, '<')) => Condition::LessThan,
+        _ => return Err(ParseError::UnknownCondition.at(span(next))),
+    };
+
+    let value_str = chars.as_str();
+    let val = value_str
+        .parse::<Value>()
+        .map_err(|e| ParseError::InvalidInt(e).at(value_str))?;
 
     Ok(Rule {
         qual,
@@ -180,33 +223,32 @@ fn parse_rule(input: &str) -> Result<Rule, ParseError> {
     })
 }
 
-fn parse_workflow(line: &str) -> Result<Workflow, ParseError> {
-    let (name, rest) = line.split_once('{').ok_or(ParseError::Expected('{'))?;
+fn parse_workflow(line: &str) -> Result<Workflow<'_>, Spanned<ParseError>> {
+    let (name, rest) = line
+        .split_once('{')
+        .ok_or(ParseError::Expected('{').at(line))?;
 
-    let inner = rest.strip_suffix('}').ok_or(ParseError::Expected('}'))?;
+    let inner = rest
+        .strip_suffix('}')
+        .ok_or(ParseError::Expected('}').at(&rest[rest.len()..]))?;
 
     let mut rules: Vec<_> = Vec::new();
     let mut destination: Option<&str> = None;
     for rule_str in inner.split(',') {
-        let rule_res = parse_rule(rule_str);
-        match rule_res {
-            Err(e) => {
-                if destination.is_none() {
-                    destination = Some(rule_str);
-                } else {
-                    return Err(e);
-                }
-            }
-            Ok(rule) => {
-                rules.push(rule);
-            }
+        if rule_str.contains(':') {
+            rules.push(parse_rule(rule_str)?);
+        } else if destination.is_none() {
+            destination = Some(rule_str);
+        } else {
+            return Err(ParseError::Expected(':').at(rule_str));
         }
     }
 
     Ok(Workflow {
         name,
         rules,
-        default_destination: destination.ok_or(ParseError::ExpectedDefaultDestination)?,
+        default_destination: destination
+            .ok_or(ParseError::ExpectedDefaultDestination.at(&inner[inner.len()..]))?,
     })
 }
 
@@ -225,8 +267,8 @@ fn run_part_through_workflows<'a>(
     cur
 }
 
-pub fn part_one(input: &str) -> Option<Value> {
-    let input = parse(input).expect("should parse");
+pub fn part_one(input: &str) -> Result<Value, InputError> {
+    let input = parse(input)?;
 
     let start = "in";
     let accepted = "A";
@@ -238,11 +280,90 @@ pub fn part_one(input: &str) -> Option<Value> {
         .filter(|part| run_part_through_workflows(part, &input.workflows, start, &ends) == accepted)
         .collect();
 
-    Some(accepted_parts.iter().map(|p| p.combined()).sum())
+    Ok(accepted_parts.iter().map(|p| p.combined()).sum())
+}
+
+/// All `x/m/a/s` rating combinations, one axis per quality.
+type Ratings = Cuboid<Value, 4>;
+
+impl Quality {
+    fn axis(&self) -> usize {
+        match self {
+            Quality::X => 0,
+            Quality::M => 1,
+            Quality::A => 2,
+            Quality::S => 3,
+        }
+    }
+}
+
+impl Rule<'_> {
+    /// Splits `ratings` into the part matching this rule and the part that does not.
+    fn split(&self, ratings: &Ratings) -> (Option<Ratings>, Option<Ratings>) {
+        let axis = self.qual.axis();
+        match self.cond {
+            Condition::LessThan => ratings.split_at(axis, self.val),
+            Condition::GreaterThan => {
+                let (below, above) = ratings.split_at(axis, self.val + 1);
+                (above, below)
+            }
+        }
+    }
+}
+
+/// Number of rating combinations in `ratings` that end up accepted, starting at workflow `start`.
+///
+/// Fails on a rule sending ratings to a workflow that does not exist, located in `input`.
+fn accepted_combinations(
+    input: &str,
+    workflows: &HashMap<&str, Workflow<'_>>,
+    start: &str,
+    ratings: Ratings,
+) -> Result<Value, InputError> {
+    let mut pending = vec![(start, ratings)];
+    let mut accepted = 0;
+
+    while let Some((name, ratings)) = pending.pop() {
+        match name {
+            "A" => {
+                accepted += ratings.volume();
+                continue;
+            }
+            "R" => continue,
+            _ => {}
+        }
+
+        let workflow = workflows
+            .get(name)
+            .ok_or_else(|| InputError::new(input, name, format!("no workflow named `{name}`")))?;
+        let mut rest = Some(ratings);
+        for rule in &workflow.rules {
+            let Some(remaining) = rest else {
+                break;
+            };
+            let (matching, others) = rule.split(&remaining);
+            if let Some(matching) = matching {
+                pending.push((rule.destination, matching));
+            }
+            rest = others;
+        }
+        if let Some(rest) = rest {
+            pending.push((workflow.default_destination, rest));
+        }
+    }
+
+    Ok(accepted)
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    None
+pub fn part_two(input: &str) -> Result<Value, InputError> {
+    let system = parse(input)?;
+    if !system.workflows.contains_key("in") {
+        return Err(InputError::unlocated("expected a workflow named `in`"));
+    }
+
+    let all = Cuboid::new([Interval::inclusive(1, 4000); 4]);
+
+    accepted_combinations(input, &system.workflows, "in", all)
 }
 
 #[cfg(test)]
@@ -252,13 +373,86 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(19114));
+        assert_eq!(result, Ok(19114));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Ok(167409079868000));
+    }
+
+    #[test]
+    fn test_part_two_unknown_workflow() {
+        let input = "in{s<1351:px,qqz}\npx{A}\n\n";
+        let error = part_two(input).unwrap_err();
+
+        assert_eq!(error.message(), "no workflow named `qqz`");
+        assert_eq!(error.location(), Some((1, 14)));
+
+        let error = part_two("px{A}\n\n").unwrap_err();
+        assert_eq!(error.message(), "expected a workflow named `in`");
+        assert_eq!(error.location(), None);
+    }
+
+    #[test]
+    fn test_rule_split() {
+        let all = Cuboid::new([Interval::inclusive(1, 4000); 4]);
+        let rule = Rule {
+            qual: Quality::M,
+            cond: Condition::GreaterThan,
+            val: 2090,
+            destination: "A",
+        };
+
+        let (matching, others) = rule.split(&all);
+        assert_eq!(matching.unwrap().axes[1], Interval::inclusive(2091, 4000));
+        assert_eq!(others.unwrap().axes[1], Interval::inclusive(1, 2090));
+        assert_eq!(matching.unwrap().axes[0], all.axes[0]);
+    }
+
+    #[test]
+    fn test_parse_error_context() {
+        let input = "px{a<2006:qkq,m>2090:A,rfg}\nqs{s>3448:A}\n\n{x=787,m=2655,a=1222,s=2876}\n";
+        let error = parse(input).err().expect("should not parse");
+
+        assert_eq!(
+            error.to_string(),
+            "error: expected a default destination\n --> 2:12\n  |\n2 | qs{s>3448:A}\n  |            ^\n  = while parsing workflow `qs`"
+        );
+    }
+
+    #[test]
+    fn test_parse_error_in_part() {
+        let input = "in{A}\n\n{x=787,m=2655,b=1222,s=2876}\n";
+        let error = parse(input).err().expect("should not parse");
+
+        assert_eq!(error.message(), "expected `a`");
+        assert_eq!(error.location(), Some((3, 15)));
+    }
+
+    #[test]
+    fn test_parse_error_in_rule() {
+        let input = "qs{s>3448:A,s>x:lnx,R}\n\n";
+        let error = parse(input).err().expect("should not parse");
+
+        assert!(error.message().starts_with("invalid rating"));
+        assert_eq!(error.location(), Some((1, 15)));
+    }
+
+    #[test]
+    fn test_parse_error_unknown_quality_and_condition() {
+        let error = parse("qs{é>3448:A,R}\n\n").err().expect("should not parse");
+        assert_eq!(error.message(), "expected a rating `x`, `m`, `a` or `s`");
+        assert_eq!(error.location(), Some((1, 4)));
+
+        let error = parse("qs{s=3448:A,R}\n\n").err().expect("should not parse");
+        assert_eq!(error.message(), "expected `<` or `>`");
+        assert_eq!(error.location(), Some((1, 5)));
+
+        let error = parse("qs{sé3448:A,R}\n\n").err().expect("should not parse");
+        assert_eq!(error.message(), "expected `<` or `>`");
+        assert_eq!(error.location(), Some((1, 5)));
     }
 
     #[test]
@@ -0,0 +1,383 @@
+use std::collections::VecDeque;
+use std::fmt::Write;
+
+use advent_of_code::error::InputError;
+use advent_of_code::math::lcm_all;
+use advent_of_code::verbose;
+
+advent_of_code::solution!(20);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Broadcaster,
+    FlipFlop,
+    Conjunction,
+    /// Only ever receives pulses, like `output` or `rx`.
+    Sink,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pulse {
+    Low,
+    High,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Module<'a> {
+    name: &'a str,
+    kind: Kind,
+    outputs: Vec<usize>,
+    inputs: Vec<usize>,
+}
+
+/// The modules, addressed by index.
+#[derive(Debug)]
+struct Network<'a> {
+    modules: Vec<Module<'a>>,
+    broadcaster: usize,
+}
+
+const BUTTON: usize = usize::MAX;
+
+/// How often part two presses the button before giving up on finding every cycle.
+const MAX_PRESSES: u64 = 1_000_000;
+
+impl<'a> Network<'a> {
+    fn index_of(&self, name: &str) -> Option<usize> {
+        self.modules.iter().position(|m| m.name == name)
+    }
+
+    /// The network as a Graphviz DOT digraph.
+    fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph modules {\n");
+        for module in &self.modules {
+            let (prefix, shape) = match module.kind {
+                Kind::Broadcaster => ("", "box"),
+                Kind::FlipFlop => ("%", "ellipse"),
+                Kind::Conjunction => ("&", "invhouse"),
+                Kind::Sink => ("", "doublecircle"),
+            };
+            let _ = writeln!(
+                dot,
+                "  \"{}\" [label=\"{prefix}{}\", shape={shape}];",
+                module.name, module.name
+            );
+        }
+        for module in &self.modules {
+            for &output in &module.outputs {
+                let _ = writeln!(
+                    dot,
+                    "  \"{}\" -> \"{}\";",
+                    module.name, self.modules[output].name
+                );
+            }
+        }
+        dot.push('}');
+        dot
+    }
+}
+
+fn parse(input: &str) -> Result<Network<'_>, InputError> {
+    let mut declared = Vec::new();
+    for line in input.lines().filter(|line| !line.is_empty()) {
+        let (name, outputs) = line
+            .split_once(" -> ")
+            .ok_or_else(|| InputError::new(input, line, "expected `->`"))?;
+        let (kind, name) = if let Some(name) = name.strip_prefix('%') {
+            (Kind::FlipFlop, name)
+        } else if let Some(name) = name.strip_prefix('&') {
+            (Kind::Conjunction, name)
+        } else if name == "broadcaster" {
+            (Kind::Broadcaster, name)
+        } else {
+            return Err(InputError::new(
+                input,
+                name,
+                "expected `%`, `&` or `broadcaster`",
+            ));
+        };
+        if name.is_empty() {
+            return Err(InputError::new(input, line, "expected a module name"));
+        }
+        declared.push((name, kind, outputs.split(", ").collect::<Vec<_>>()));
+    }
+
+    let mut modules: Vec<Module> = declared
+        .iter()
+        .map(|&(name, kind, _)| Module {
+            name,
+            kind,
+            outputs: Vec::new(),
+            inputs: Vec::new(),
+        })
+        .collect();
+
+    for (from, (_, _, outputs)) in declared.iter().enumerate() {
+        for &output in outputs {
+            let to = match modules.iter().position(|m| m.name == output) {
+                Some(to) => to,
+                None => {
+                    modules.push(Module {
+                        name: output,
+                        kind: Kind::Sink,
+                        outputs: Vec::new(),
+                        inputs: Vec::new(),
+                    });
+                    modules.len() - 1
+                }
+            };
+            modules[from].outputs.push(to);
+            modules[to].inputs.push(from);
+        }
+    }
+
+    let broadcaster = modules
+        .iter()
+        .position(|m| m.kind == Kind::Broadcaster)
+        .ok_or_else(|| InputError::unlocated("expected a `broadcaster` module"))?;
+
+    Ok(Network {
+        modules,
+        broadcaster,
+    })
+}
+
+/// The state of all flip-flops and conjunction memories.
+struct Simulator<'n, 'a> {
+    network: &'n Network<'a>,
+    on: Vec<bool>,
+    /// Last pulse received from each input, in the order of [`Module::inputs`].
+    memory: Vec<Vec<Pulse>>,
+}
+
+impl<'n, 'a> Simulator<'n, 'a> {
+    fn new(network: &'n Network<'a>) -> Self {
+        Simulator {
+            network,
+            on: vec![false; network.modules.len()],
+            memory: network
+                .modules
+                .iter()
+                .map(|m| vec![Pulse::Low; m.inputs.len()])
+                .collect(),
+        }
+    }
+
+    /// Sends a low pulse to the broadcaster and processes pulses in order until none are left,
+    /// calling `observe` with `(from, to, pulse)` for each (`from` is [`BUTTON`] for the first).
+    fn push_button(&mut self, mut observe: impl FnMut(usize, usize, Pulse)) {
+        let mut queue = VecDeque::from([(BUTTON, self.network.broadcaster, Pulse::Low)]);
+
+        while let Some((from, to, pulse)) = queue.pop_front() {
+            observe(from, to, pulse);
+
+            let module = &self.network.modules[to];
+            let sent = match module.kind {
+                Kind::Broadcaster => Some(pulse),
+                Kind::FlipFlop if pulse == Pulse::Low => {
+                    self.on[to] = !self.on[to];
+                    Some(if self.on[to] { Pulse::High } else { Pulse::Low })
+                }
+                Kind::FlipFlop | Kind::Sink => None,
+                Kind::Conjunction => {
+                    let input = module.inputs.iter().position(|&i| i == from);
+                    if let Some(input) = input {
+                        self.memory[to][input] = pulse;
+                    }
+                    let all_high = self.memory[to].iter().all(|&p| p == Pulse::High);
+                    Some(if all_high { Pulse::Low } else { Pulse::High })
+                }
+            };
+
+            if let Some(sent) = sent {
+                queue.extend(module.outputs.iter().map(|&output| (to, output, sent)));
+            }
+        }
+    }
+}
+
+pub fn part_one(input: &str) -> Result<u64, InputError> {
+    let network = parse(input)?;
+    let mut simulator = Simulator::new(&network);
+
+    let (mut low, mut high) = (0, 0);
+    for _ in 0..1000 {
+        simulator.push_button(|_, _, pulse| match pulse {
+            Pulse::Low => low += 1,
+            Pulse::High => high += 1,
+        });
+    }
+
+    Ok(low * high)
+}
+
+pub fn part_two(input: &str) -> Result<u64, InputError> {
+    let network = parse(input)?;
+    verbose!("{}", network.to_dot());
+
+    // `rx` is fed by a single conjunction, which sends a low pulse once all its inputs sent high.
+    let feeder = network
+        .index_of("rx")
+        .and_then(|rx| match network.modules[rx].inputs[..] {
+            [feeder] if network.modules[feeder].kind == Kind::Conjunction => Some(feeder),
+            _ => None,
+        })
+        .ok_or_else(|| InputError::unlocated("expected one conjunction feeding `rx`"))?;
+    let inputs = &network.modules[feeder].inputs;
+
+    // The presses at which each input first sent a high pulse to the feeder, and the next time.
+    let mut high_at: Vec<Vec<u64>> = vec![Vec::new(); inputs.len()];
+    let mut simulator = Simulator::new(&network);
+    let mut presses = 0;
+    while high_at.iter().any(|presses| presses.len() < 2) {
+        if presses == MAX_PRESSES {
+            return Err(InputError::unlocated(format!(
+                "an input of `rx`'s feeder did not cycle within {MAX_PRESSES} presses"
+            )));
+        }
+        presses += 1;
+        simulator.push_button(|from, to, pulse| {
+            if to == feeder && pulse == Pulse::High {
+                let input = inputs.iter().position(|&i| i == from).unwrap();
+                if high_at[input].last() != Some(&presses) {
+                    high_at[input].push(presses);
+                }
+            }
+        });
+    }
+
+    for (&input, presses) in inputs.iter().zip(&high_at) {
+        verbose!(
+            "{} sends high after {} presses, then every {}",
+            network.modules[input].name,
+            presses[0],
+            presses[1] - presses[0]
+        );
+    }
+
+    // Each input sends high every `length` presses from the start, so they first line up at the lcm.
+    let lengths = high_at
+        .iter()
+        .map(|presses| {
+            let length = presses[1] - presses[0];
+            (presses[0] == length).then_some(length)
+        })
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| {
+            InputError::unlocated("expected the feeder's inputs to cycle from the start")
+        })?;
+
+    lcm_all::<u64>(lengths).ok_or_else(|| InputError::unlocated("the number of presses overflows"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `ia` sends high on every second press and `ib` on every fourth, so `rx` gets a low pulse
+    /// after 4 presses.
+    const COUNTER: &str = "\
+broadcaster -> a
+%a -> b, ia
+%b -> ib
+&ia -> x
+&ib -> x
+&x -> rx
+";
+
+    #[test]
+    fn test_parse_example() {
+        let input = advent_of_code::template::read_file_part("examples", DAY, 2);
+        let network = parse(&input).unwrap();
+
+        let names: Vec<_> = network.modules.iter().map(|m| m.name).collect();
+        assert_eq!(names, ["broadcaster", "a", "inv", "b", "con", "output"]);
+        assert_eq!(
+            network.modules[4],
+            Module {
+                name: "con",
+                kind: Kind::Conjunction,
+                outputs: vec![5],
+                inputs: vec![1, 3],
+            }
+        );
+    }
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Ok(32000000));
+    }
+
+    #[test]
+    fn test_part_one_example_two() {
+        let result = part_one(&advent_of_code::template::read_file_part(
+            "examples", DAY, 2,
+        ));
+        assert_eq!(result, Ok(11687500));
+    }
+
+    #[test]
+    fn test_part_two() {
+        let network = parse(COUNTER).unwrap();
+        let rx = network.index_of("rx").unwrap();
+
+        let mut simulator = Simulator::new(&network);
+        let mut presses = 0;
+        let mut reached = false;
+        while !reached {
+            presses += 1;
+            simulator.push_button(|_, to, pulse| reached |= to == rx && pulse == Pulse::Low);
+        }
+
+        assert_eq!(presses, 4);
+        assert_eq!(part_two(COUNTER), Ok(4));
+    }
+
+    #[test]
+    fn test_part_two_without_cycle() {
+        // `a` sends a high pulse on the first press, `b` never does.
+        let input = "broadcaster -> a\n%a -> x\n&b -> x\n&x -> rx\n";
+
+        let error = part_two(input).unwrap_err();
+        assert_eq!(
+            error.message(),
+            "an input of `rx`'s feeder did not cycle within 1000000 presses"
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse("broadcaster -> a\n -> a\n").unwrap_err();
+        assert_eq!(error.message(), "expected `%`, `&` or `broadcaster`");
+        assert_eq!(error.location(), Some((2, 1)));
+
+        let error = parse("broadcaster -> a\n% -> a\n").unwrap_err();
+        assert_eq!(error.message(), "expected a module name");
+        assert_eq!(error.location(), Some((2, 1)));
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let network = parse(&input).unwrap();
+
+        assert_eq!(
+            network.to_dot(),
+            r#"digraph modules {
+  "broadcaster" [label="broadcaster", shape=box];
+  "a" [label="%a", shape=ellipse];
+  "b" [label="%b", shape=ellipse];
+  "c" [label="%c", shape=ellipse];
+  "inv" [label="&inv", shape=invhouse];
+  "broadcaster" -> "a";
+  "broadcaster" -> "b";
+  "broadcaster" -> "c";
+  "a" -> "b";
+  "b" -> "c";
+  "c" -> "inv";
+  "inv" -> "a";
+}"#
+        );
+    }
+}
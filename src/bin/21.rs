@@ -0,0 +1,190 @@
+use std::collections::{HashMap, VecDeque};
+
+use advent_of_code::error::InputError;
+use advent_of_code::geometry::{step, Direction};
+use advent_of_code::grid::{Grid, GridPoint};
+use advent_of_code::verbose;
+
+advent_of_code::solution!(21);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tile {
+    Start,
+    Plot,
+    Rock,
+}
+
+impl Tile {
+    fn from_char(c: char) -> Option<Tile> {
+        match c {
+            'S' => Some(Tile::Start),
+            '.' => Some(Tile::Plot),
+            '#' => Some(Tile::Rock),
+            _ => None,
+        }
+    }
+}
+
+struct Garden {
+    tiles: Grid<Tile>,
+    start: GridPoint,
+}
+
+/// Whether the garden ends at its edges or repeats infinitely in every direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Layout {
+    Single,
+    Tiled,
+}
+
+fn parse(input: &str) -> Result<Garden, InputError> {
+    let tiles = Grid::parse(input, Tile::from_char)?;
+    let start = tiles
+        .find(|&tile| tile == Tile::Start)
+        .ok_or_else(|| InputError::unlocated("expected a starting position `S`"))?;
+
+    Ok(Garden { tiles, start })
+}
+
+impl Garden {
+    fn is_plot(&self, (x, y): GridPoint, layout: Layout) -> bool {
+        let point = match layout {
+            Layout::Single => (x, y),
+            Layout::Tiled => (
+                x.rem_euclid(self.tiles.width() as isize),
+                y.rem_euclid(self.tiles.height() as isize),
+            ),
+        };
+        self.tiles
+            .get(point)
+            .is_some_and(|&tile| tile != Tile::Rock)
+    }
+
+    /// Number of plots the elf can end on after exactly `steps` steps.
+    ///
+    /// The elf can step back and forth, so a plot counts if it is reached within `steps` steps
+    /// and its distance has the same parity as `steps`.
+    fn reachable(&self, steps: usize, layout: Layout) -> usize {
+        let mut distances = HashMap::from([(self.start, 0)]);
+        let mut queue = VecDeque::from([(self.start, 0)]);
+
+        while let Some((point, distance)) = queue.pop_front() {
+            if distance == steps {
+                continue;
+            }
+            for direction in Direction::ALL {
+                let next = step(point, direction, 1);
+                if self.is_plot(next, layout) && !distances.contains_key(&next) {
+                    distances.insert(next, distance + 1);
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+
+        distances
+            .values()
+            .filter(|&&distance| distance % 2 == steps % 2)
+            .count()
+    }
+
+    /// Number of plots reachable in exactly `steps` steps on the tiled garden, for large `steps`.
+    ///
+    /// With a square garden of width `w` and clear lines out of the start, the reachable count
+    /// after `n + k * w` steps is a quadratic in `k`, so three samples are enough to extrapolate.
+    fn extrapolated(&self, steps: usize) -> Option<u64> {
+        let width = self.tiles.width();
+        if width != self.tiles.height() {
+            return None;
+        }
+
+        let (k, n) = (steps / width, steps % width);
+        let [f0, f1, f2] = [0, 1, 2].map(|i| self.reachable(n + i * width, Layout::Tiled) as i64);
+        verbose!(
+            "{f0}, {f1} and {f2} plots after {}, {} and {} steps",
+            n,
+            n + width,
+            n + 2 * width
+        );
+
+        // Newton's forward differences: f(k) = f0 + k Δ1 + k (k - 1) / 2 Δ2.
+        let (first, second) = (f1 - f0, f2 - 2 * f1 + f0);
+        let k = k as i64;
+        let plots = f0 + k * first + k * (k - 1) / 2 * second;
+
+        u64::try_from(plots).ok()
+    }
+}
+
+pub fn part_one(input: &str) -> Result<usize, InputError> {
+    let garden = parse(input)?;
+
+    Ok(garden.reachable(64, Layout::Single))
+}
+
+pub fn part_two(input: &str) -> Result<u64, InputError> {
+    let garden = parse(input)?;
+
+    garden
+        .extrapolated(26501365)
+        .ok_or_else(|| InputError::unlocated("expected a square garden"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A square garden with clear edges and clear lines out of the start.
+    const OPEN: &str = "\
+.........
+.##...#..
+.#....##.
+...#.....
+....S....
+..#...#..
+.##...#..
+.....#...
+.........
+";
+
+    fn example() -> Garden {
+        parse(&advent_of_code::template::read_file("examples", DAY)).unwrap()
+    }
+
+    #[test]
+    fn test_reachable() {
+        assert_eq!(example().reachable(6, Layout::Single), 16);
+    }
+
+    #[test]
+    fn test_part_one() {
+        // After 64 steps the elf can reach every plot of the example with an even distance.
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Ok(42));
+    }
+
+    #[test]
+    fn test_tiled() {
+        let garden = example();
+
+        for (steps, plots) in [(6, 16), (10, 50), (50, 1594), (100, 6536), (500, 167004)] {
+            assert_eq!(
+                garden.reachable(steps, Layout::Tiled),
+                plots,
+                "{steps} steps"
+            );
+        }
+    }
+
+    #[test]
+    fn test_extrapolated() {
+        let garden = parse(OPEN).unwrap();
+
+        for steps in [4 + 9 * 3, 4 + 9 * 5, 4 + 9 * 8] {
+            assert_eq!(
+                garden.extrapolated(steps),
+                Some(garden.reachable(steps, Layout::Tiled) as u64),
+                "{steps} steps"
+            );
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::VecDeque;
+
+use advent_of_code::error::InputError;
+use advent_of_code::grid::Grid;
+use advent_of_code::interval::{Cuboid, Interval};
+use advent_of_code::parse;
+
+advent_of_code::solution!(22);
+
+/// A brick's `x`, `y` and `z` extent.
+type Brick = Cuboid<usize, 3>;
+
+const Z: usize = 2;
+
+fn parse(input: &str) -> Result<Vec<Brick>, InputError> {
+    input
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| parse_brick(line, input))
+        .collect()
+}
+
+fn parse_brick(line: &str, input: &str) -> Result<Brick, InputError> {
+    let (start, end) = parse::split_once(line, '~').map_err(|e| e.locate(input))?;
+    let corner = |s| -> Result<[usize; 3], InputError> {
+        let coordinates: Vec<usize> = parse::uints(s).map_err(|e| e.locate(input))?;
+        coordinates
+            .try_into()
+            .map_err(|_| InputError::new(input, s, "expected three coordinates"))
+    };
+    let (start, end) = (corner(start)?, corner(end)?);
+
+    Ok(Brick::new(std::array::from_fn(|axis| {
+        Interval::inclusive(start[axis].min(end[axis]), start[axis].max(end[axis]))
+    })))
+}
+
+/// The bricks after falling, with which bricks rest on which.
+#[derive(Debug)]
+struct Stack {
+    /// The settled bricks, in input order.
+    bricks: Vec<Brick>,
+    /// The bricks each brick rests directly on.
+    supported_by: Vec<Vec<usize>>,
+    /// The bricks resting directly on each brick.
+    supports: Vec<Vec<usize>>,
+}
+
+/// Lets all bricks fall as far as they can, lowest first, tracking the top of each column.
+fn settle(bricks: &[Brick]) -> Stack {
+    let width = bricks.iter().map(|b| b.axes[0].end).max().unwrap_or(0);
+    let depth = bricks.iter().map(|b| b.axes[1].end).max().unwrap_or(0);
+    // The lowest free `z` of each column and the brick on top of it, if any.
+    let mut heights: Grid<(usize, Option<usize>)> = Grid::filled(width, depth, (1, None));
+
+    let mut order: Vec<usize> = (0..bricks.len()).collect();
+    order.sort_by_key(|&i| bricks[i].axes[Z].start);
+
+    let mut settled = bricks.to_vec();
+    let mut supported_by = vec![Vec::new(); bricks.len()];
+    let mut supports = vec![Vec::new(); bricks.len()];
+
+    for i in order {
+        let brick = bricks[i];
+        let footprint: Vec<_> = (brick.axes[1].start..brick.axes[1].end)
+            .flat_map(|y| (brick.axes[0].start..brick.axes[0].end).map(move |x| (x, y)))
+            .map(|(x, y)| (x as isize, y as isize))
+            .collect();
+
+        let rest = footprint
+            .iter()
+            .map(|&point| heights[point].0)
+            .max()
+            .unwrap_or(1);
+        for &point in &footprint {
+            if let (top, Some(below)) = heights[point] {
+                if top == rest && !supported_by[i].contains(&below) {
+                    supported_by[i].push(below);
+                    supports[below].push(i);
+                }
+            }
+        }
+
+        settled[i].axes[Z] = Interval::from_len(rest, brick.axes[Z].len());
+        for &point in &footprint {
+            heights[point] = (settled[i].axes[Z].end, Some(i));
+        }
+    }
+
+    Stack {
+        bricks: settled,
+        supported_by,
+        supports,
+    }
+}
+
+impl Stack {
+    /// Whether removing `brick` leaves every brick it supports resting on another.
+    fn can_disintegrate(&self, brick: usize) -> bool {
+        self.supports[brick]
+            .iter()
+            .all(|&above| self.supported_by[above].len() > 1)
+    }
+
+    /// Number of other bricks that fall in the chain reaction after removing `brick`.
+    fn chain_reaction(&self, brick: usize) -> usize {
+        let mut fallen = vec![false; self.bricks.len()];
+        fallen[brick] = true;
+        let mut queue = VecDeque::from([brick]);
+        let mut count = 0;
+
+        while let Some(current) = queue.pop_front() {
+            for &above in &self.supports[current] {
+                if !fallen[above] && self.supported_by[above].iter().all(|&b| fallen[b]) {
+                    fallen[above] = true;
+                    count += 1;
+                    queue.push_back(above);
+                }
+            }
+        }
+
+        count
+    }
+}
+
+pub fn part_one(input: &str) -> Result<usize, InputError> {
+    let stack = settle(&parse(input)?);
+
+    Ok((0..stack.bricks.len())
+        .filter(|&brick| stack.can_disintegrate(brick))
+        .count())
+}
+
+pub fn part_two(input: &str) -> Result<usize, InputError> {
+    let stack = settle(&parse(input)?);
+
+    Ok((0..stack.bricks.len())
+        .map(|brick| stack.chain_reaction(brick))
+        .sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settle_example() {
+        let stack = settle(&parse(&advent_of_code::template::read_file("examples", DAY)).unwrap());
+
+        let bottoms: Vec<_> = stack.bricks.iter().map(|b| b.axes[Z].start).collect();
+        assert_eq!(bottoms, [1, 2, 2, 3, 3, 4, 5]);
+        assert_eq!(stack.supported_by[3], [1, 2]);
+        assert_eq!(stack.supports[5], [6]);
+    }
+
+    #[test]
+    fn test_settle_lowest_first() {
+        // The top brick comes first in the input, but has to land on the one below it.
+        let stack = settle(&parse("0,0,5~2,0,5\n1,0,2~1,0,3\n").unwrap());
+
+        let extents: Vec<_> = stack.bricks.iter().map(|b| b.axes[Z]).collect();
+        assert_eq!(extents, [Interval::new(3, 4), Interval::new(1, 3)]);
+        assert_eq!(stack.supported_by, [vec![1], vec![]]);
+    }
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Ok(5));
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Ok(7));
+    }
+}
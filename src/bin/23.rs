@@ -0,0 +1,227 @@
+use advent_of_code::error::InputError;
+use advent_of_code::geometry::Direction;
+use advent_of_code::grid::{Grid, GridPoint};
+use advent_of_code::verbose;
+
+advent_of_code::solution!(23);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tile {
+    Path,
+    Forest,
+    Slope(Direction),
+}
+
+impl Tile {
+    fn from_char(c: char) -> Option<Tile> {
+        match c {
+            '.' => Some(Tile::Path),
+            '#' => Some(Tile::Forest),
+            '^' => Some(Tile::Slope(Direction::North)),
+            '>' => Some(Tile::Slope(Direction::East)),
+            'v' => Some(Tile::Slope(Direction::South)),
+            '<' => Some(Tile::Slope(Direction::West)),
+            _ => None,
+        }
+    }
+}
+
+/// Whether slopes can only be walked downhill (part one) or are just paths (part two).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Slopes {
+    Slippery,
+    Climbable,
+}
+
+struct Map {
+    tiles: Grid<Tile>,
+    start: GridPoint,
+    end: GridPoint,
+}
+
+fn parse(input: &str) -> Result<Map, InputError> {
+    let tiles = Grid::parse(input, Tile::from_char)?;
+    let last = tiles.height() as isize - 1;
+    let path_in_row = |y| {
+        (0..tiles.width() as isize)
+            .map(|x| (x, y))
+            .find(|&point| tiles[point] == Tile::Path)
+    };
+
+    let start =
+        path_in_row(0).ok_or_else(|| InputError::unlocated("expected a path in the top row"))?;
+    let end = path_in_row(last)
+        .ok_or_else(|| InputError::unlocated("expected a path in the bottom row"))?;
+
+    Ok(Map { tiles, start, end })
+}
+
+/// The maze compressed to its junctions, with the length of the trail between each pair.
+#[derive(Debug)]
+struct Trails {
+    /// The junction each node stands for; the start is node 0 and the end node 1.
+    nodes: Vec<GridPoint>,
+    /// Outgoing `(node, length)` edges of each node.
+    edges: Vec<Vec<(usize, usize)>>,
+}
+
+const START: usize = 0;
+const END: usize = 1;
+
+impl Map {
+    /// Where a single step from `point` can go, without turning back onto `from`.
+    fn steps(
+        &self,
+        point: GridPoint,
+        from: Option<GridPoint>,
+        slopes: Slopes,
+    ) -> impl Iterator<Item = GridPoint> + '_ {
+        let here = self.tiles[point];
+        self.tiles
+            .neighbours(point)
+            .filter(move |&(direction, _)| match (here, slopes) {
+                (Tile::Slope(downhill), Slopes::Slippery) => direction == downhill,
+                _ => true,
+            })
+            .map(|(_, next)| next)
+            .filter(move |&next| Some(next) != from && self.tiles[next] != Tile::Forest)
+    }
+
+    fn trails(&self, slopes: Slopes) -> Trails {
+        let mut nodes = vec![self.start, self.end];
+        nodes.extend(self.tiles.points().filter(|&point| {
+            self.tiles[point] != Tile::Forest
+                && self.steps(point, None, Slopes::Climbable).count() >= 3
+        }));
+
+        let edges = nodes
+            .iter()
+            .map(|&node| {
+                let mut edges: Vec<(usize, usize)> = Vec::new();
+                for first in self.steps(node, None, slopes) {
+                    let Some((to, length)) = self.follow(&nodes, node, first, slopes) else {
+                        continue;
+                    };
+                    match edges.iter_mut().find(|(other, _)| *other == to) {
+                        Some(edge) => edge.1 = edge.1.max(length),
+                        None => edges.push((to, length)),
+                    }
+                }
+                edges
+            })
+            .collect();
+
+        Trails { nodes, edges }
+    }
+
+    /// Walks the corridor from `node` through `first` to the next junction.
+    fn follow(
+        &self,
+        nodes: &[GridPoint],
+        node: GridPoint,
+        first: GridPoint,
+        slopes: Slopes,
+    ) -> Option<(usize, usize)> {
+        let (mut previous, mut current, mut length) = (node, first, 1);
+        loop {
+            if let Some(to) = nodes.iter().position(|&n| n == current) {
+                return Some((to, length));
+            }
+            let next = self.steps(current, Some(previous), slopes).next()?;
+            (previous, current) = (current, next);
+            length += 1;
+        }
+    }
+}
+
+impl Trails {
+    fn length(&self, from: usize, to: usize) -> Option<usize> {
+        self.edges[from]
+            .iter()
+            .find(|&&(node, _)| node == to)
+            .map(|&(_, length)| length)
+    }
+
+    /// Length of the longest trail from the start to the end that visits no junction twice.
+    fn longest(&self) -> Option<usize> {
+        // The end's only neighbour has to go straight to the end, or the path would be cut off.
+        let into_end: Vec<usize> = (0..self.nodes.len())
+            .filter(|&node| self.length(node, END).is_some())
+            .collect();
+        let (last, last_length) = match into_end[..] {
+            [node] => (node, self.length(node, END)?),
+            _ => (END, 0),
+        };
+
+        self.longest_from(START, 1 << START, last)
+            .map(|length| length + last_length)
+    }
+
+    fn longest_from(&self, node: usize, visited: u64, last: usize) -> Option<usize> {
+        if node == last {
+            return Some(0);
+        }
+        self.edges[node]
+            .iter()
+            .filter(|&&(to, _)| visited & (1 << to) == 0)
+            .filter_map(|&(to, length)| {
+                Some(length + self.longest_from(to, visited | (1 << to), last)?)
+            })
+            .max()
+    }
+}
+
+fn solve(input: &str, slopes: Slopes) -> Result<usize, InputError> {
+    let map = parse(input)?;
+    let trails = map.trails(slopes);
+    verbose!(
+        "{} junctions, {} trails",
+        trails.nodes.len(),
+        trails.edges.iter().map(Vec::len).sum::<usize>()
+    );
+    if trails.nodes.len() > 64 {
+        return Err(InputError::unlocated("expected at most 64 junctions"));
+    }
+
+    trails
+        .longest()
+        .ok_or_else(|| InputError::unlocated("no trail reaches the end"))
+}
+
+pub fn part_one(input: &str) -> Result<usize, InputError> {
+    solve(input, Slopes::Slippery)
+}
+
+pub fn part_two(input: &str) -> Result<usize, InputError> {
+    solve(input, Slopes::Climbable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trails() {
+        let map = parse(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+
+        let slippery = map.trails(Slopes::Slippery);
+        assert_eq!(slippery.nodes.len(), 9);
+        assert_eq!(slippery.edges[START].len(), 1);
+        assert!(slippery.edges[END].is_empty());
+
+        let climbable = map.trails(Slopes::Climbable);
+        assert_eq!(climbable.edges[END].len(), 1);
+    }
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Ok(94));
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Ok(154));
+    }
+}
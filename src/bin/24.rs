@@ -0,0 +1,314 @@
+use std::ops::RangeInclusive;
+
+use advent_of_code::error::{Context, InputError};
+use advent_of_code::parse;
+use advent_of_code::template::runner::param;
+use advent_of_code::verbose;
+
+advent_of_code::solution!(24);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Hailstone {
+    position: [i64; 3],
+    velocity: [i64; 3],
+}
+
+const TEST_AREA: RangeInclusive<i64> = 200000000000000..=400000000000000;
+
+/// The test area from the puzzle, unless another is given with `--param area=7..=27`.
+fn test_area() -> Result<RangeInclusive<i64>, InputError> {
+    parse_area(param("area"))
+}
+
+/// The area described by `value`, or the one from the puzzle without a value.
+fn parse_area(value: Option<&str>) -> Result<RangeInclusive<i64>, InputError> {
+    let Some(area) = value else {
+        return Ok(TEST_AREA);
+    };
+    let bounds: Vec<i64> = parse::ints(area)
+        .map_err(|e| e.locate(area))
+        .context("while parsing the `area` parameter")?;
+    match bounds[..] {
+        [low, high] if low <= high => Ok(low..=high),
+        _ => Err(InputError::new(
+            area,
+            area,
+            "expected an area like `7..=27`",
+        ))
+        .context("while parsing the `area` parameter"),
+    }
+}
+
+fn parse(input: &str) -> Result<Vec<Hailstone>, InputError> {
+    input
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let values: Vec<i64> = parse::ints(line).map_err(|e| e.locate(input))?;
+            match values[..] {
+                [px, py, pz, vx, vy, vz] => Ok(Hailstone {
+                    position: [px, py, pz],
+                    velocity: [vx, vy, vz],
+                }),
+                _ => Err(InputError::new(
+                    input,
+                    line,
+                    "expected a position and a velocity like `19, 13, 30 @ -2, 1, -2`",
+                )),
+            }
+        })
+        .collect()
+}
+
+/// Whether the paths of `a` and `b` in the `x`/`y` plane cross inside `area` on both axes, in
+/// the future of both hailstones.
+///
+/// Everything stays an exact `i128` fraction over the determinant of the two velocities.
+fn paths_cross(a: &Hailstone, b: &Hailstone, area: &RangeInclusive<i64>) -> bool {
+    let [apx, apy, _] = a.position.map(i128::from);
+    let [avx, avy, _] = a.velocity.map(i128::from);
+    let [bpx, bpy, _] = b.position.map(i128::from);
+    let [bvx, bvy, _] = b.velocity.map(i128::from);
+
+    // a.p + t a.v = b.p + s b.v, solved with Cramer's rule.
+    let det = avx * bvy - avy * bvx;
+    if det == 0 {
+        return false;
+    }
+    let (dx, dy) = (bpx - apx, bpy - apy);
+    let t = dx * bvy - dy * bvx;
+    let s = dx * avy - dy * avx;
+
+    // Normalise to a positive denominator so the comparisons keep their direction.
+    let (det, t, s) = if det < 0 { (-det, -t, -s) } else { (det, t, s) };
+    if t < 0 || s < 0 {
+        return false;
+    }
+
+    let (low, high) = (i128::from(*area.start()), i128::from(*area.end()));
+    let inside = |p: i128, v: i128| (low * det..=high * det).contains(&(p * det + v * t));
+    inside(apx, avx) && inside(apy, avy)
+}
+
+fn crossings(hailstones: &[Hailstone], area: &RangeInclusive<i64>) -> usize {
+    hailstones
+        .iter()
+        .enumerate()
+        .flat_map(|(i, a)| hailstones[i + 1..].iter().map(move |b| (a, b)))
+        .filter(|(a, b)| paths_cross(a, b, area))
+        .count()
+}
+
+type Vector = [i128; 3];
+
+fn sub(a: Vector, b: Vector) -> Vector {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: Vector, b: Vector) -> i128 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: Vector, b: Vector) -> Vector {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+/// `numerator / denominator` if it is a whole number.
+fn exact_div(numerator: i128, denominator: i128) -> Option<i128> {
+    (denominator != 0 && numerator % denominator == 0).then(|| numerator / denominator)
+}
+
+/// The position and velocity of the rock that hits `first`, `a` and `b` at whole times.
+///
+/// Seen from `first`, which then sits still at the origin, the rock passes through the origin
+/// and through a point on the path of `a`. So it moves in the plane through the origin and
+/// `a`'s path, and hits `b` where `b`'s path crosses that plane, a linear equation in the time.
+/// The same holds with `a` and `b` swapped, and the two hits fix the rock's path. Every step is
+/// an exact `i128` division, which fails if the hits do not happen at whole times.
+fn throw(first: &Hailstone, a: &Hailstone, b: &Hailstone) -> Option<(Vector, Vector)> {
+    let origin = first.position.map(i128::from);
+    let drift = first.velocity.map(i128::from);
+    let relative = |h: &Hailstone| {
+        (
+            sub(h.position.map(i128::from), origin),
+            sub(h.velocity.map(i128::from), drift),
+        )
+    };
+    let ((pa, va), (pb, vb)) = (relative(a), relative(b));
+
+    // Time at which a hailstone moving from `p` with `v` crosses the plane with normal `normal`.
+    let crossing =
+        |normal: Vector, p: Vector, v: Vector| exact_div(-dot(normal, p), dot(normal, v));
+    let tb = crossing(cross(pa, va), pb, vb)?;
+    let ta = crossing(cross(pb, vb), pa, va)?;
+
+    let hit = |p: Vector, v: Vector, t: i128| [p[0] + t * v[0], p[1] + t * v[1], p[2] + t * v[2]];
+    let (hit_a, hit_b) = (hit(pa, va, ta), hit(pb, vb, tb));
+
+    let velocity = sub(hit_b, hit_a).map(|d| exact_div(d, tb - ta));
+    let velocity = [velocity[0]?, velocity[1]?, velocity[2]?];
+    let position = sub(hit_a, velocity.map(|v| v * ta));
+
+    // Back from `first`'s point of view to the hailstones'.
+    Some((
+        [0, 1, 2].map(|i| position[i] + origin[i]),
+        [0, 1, 2].map(|i| velocity[i] + drift[i]),
+    ))
+}
+
+/// Whether a rock thrown from `position` with `velocity` hits `hailstone` at a whole time.
+fn hits((position, velocity): (Vector, Vector), hailstone: &Hailstone) -> bool {
+    // position + t velocity = p + t v, so p - position = t (velocity - v).
+    let gap = sub(hailstone.position.map(i128::from), position);
+    let closing = sub(velocity, hailstone.velocity.map(i128::from));
+    let time = match (0..3).find(|&i| closing[i] != 0) {
+        Some(i) => exact_div(gap[i], closing[i]),
+        None => Some(0),
+    };
+    time.is_some_and(|t| t >= 0 && (0..3).all(|i| gap[i] == t * closing[i]))
+}
+
+pub fn part_one(input: &str) -> Result<usize, InputError> {
+    let hailstones = parse(input)?;
+
+    Ok(crossings(&hailstones, &test_area()?))
+}
+
+pub fn part_two(input: &str) -> Result<i128, InputError> {
+    let hailstones = parse(input)?;
+
+    let (first, others) = hailstones
+        .split_first()
+        .ok_or_else(|| InputError::unlocated("expected hailstones"))?;
+    let ([x, y, z], [vx, vy, vz]) = others
+        .iter()
+        .enumerate()
+        .flat_map(|(i, a)| others[i + 1..].iter().map(move |b| (a, b)))
+        .filter_map(|(a, b)| throw(first, a, b))
+        .find(|&rock| hailstones.iter().all(|h| hits(rock, h)))
+        .ok_or_else(|| {
+            InputError::unlocated("no rock with integer coordinates hits every hailstone")
+        })?;
+    verbose!("rock at {x}, {y}, {z} @ {vx}, {vy}, {vz}");
+
+    Ok(x + y + z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_of_code::template::runner::with_params;
+
+    #[test]
+    fn test_paths_cross() {
+        let hailstones = parse(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        let area = 7..=27;
+
+        // Inside the area.
+        assert!(paths_cross(&hailstones[0], &hailstones[1], &area));
+        // Outside the area.
+        assert!(!paths_cross(&hailstones[0], &hailstones[3], &area));
+        // Parallel.
+        assert!(!paths_cross(&hailstones[1], &hailstones[2], &area));
+        // In the past of the first hailstone.
+        assert!(!paths_cross(&hailstones[0], &hailstones[4], &area));
+    }
+
+    #[test]
+    fn test_part_one() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let result = with_params(&[("area", "7..=27")], || part_one(&input));
+        assert_eq!(result, Ok(2));
+    }
+
+    #[test]
+    fn test_parse_area() {
+        assert_eq!(parse_area(None), Ok(TEST_AREA));
+        assert_eq!(parse_area(Some("-5..=5")), Ok(-5..=5));
+
+        let error = parse_area(Some("27..=7")).unwrap_err();
+        assert_eq!(error.message(), "expected an area like `7..=27`");
+        assert!(error
+            .to_string()
+            .ends_with("= while parsing the `area` parameter"));
+    }
+
+    #[test]
+    fn test_throw() {
+        let hailstones = parse(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+
+        assert_eq!(
+            throw(&hailstones[0], &hailstones[1], &hailstones[2]),
+            Some(([24, 13, 10], [-3, 1, 2]))
+        );
+    }
+
+    #[test]
+    fn test_hits() {
+        let hailstones = parse(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        let rock = ([24, 13, 10], [-3, 1, 2]);
+
+        assert!(hailstones.iter().all(|h| hits(rock, h)));
+        assert!(!hits(rock, &parse("0, 0, 0 @ 1, 1, 1").unwrap()[0]));
+        // Where the rock was a nanosecond before the throw.
+        assert!(!hits(rock, &parse("27, 12, 8 @ 0, 0, 0").unwrap()[0]));
+    }
+
+    #[test]
+    fn test_part_two_missed_hailstone() {
+        let mut input = advent_of_code::template::read_file("examples", DAY);
+        input.push_str("0, 0, 0 @ 1, 1, 1\n");
+
+        let error = part_two(&input).unwrap_err();
+        assert_eq!(
+            error.message(),
+            "no rock with integer coordinates hits every hailstone"
+        );
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Ok(47));
+    }
+
+    #[test]
+    fn test_part_two_large_coordinates() {
+        // Hailstones at the scale of real inputs, all hit by a rock thrown from
+        // 260_000_000_000_123, 310_000_000_000_456, 190_000_000_000_789 @ -42, 17, 93.
+        let rock = (
+            [
+                260_000_000_000_123,
+                310_000_000_000_456,
+                190_000_000_000_789,
+            ],
+            [-42, 17, 93],
+        );
+        let input: String = [
+            (719_351_062_311, [-170, 331, -35]),
+            (553_019_841_105, [112, -262, 49]),
+            (904_671_326_597, [-16, 64, 242]),
+            (347_203_955_818, [211, -35, -301]),
+            (612_330_971_004, [-8, 155, 166]),
+        ]
+        .into_iter()
+        .map(|(t, velocity): (i64, [i64; 3])| {
+            let position: [i64; 3] =
+                std::array::from_fn(|i| rock.0[i] + t * (rock.1[i] - velocity[i]));
+            format!(
+                "{}, {}, {} @ {}, {}, {}\n",
+                position[0], position[1], position[2], velocity[0], velocity[1], velocity[2]
+            )
+        })
+        .collect();
+
+        assert_eq!(
+            part_two(&input),
+            Ok(260_000_000_000_123 + 310_000_000_000_456 + 190_000_000_000_789)
+        );
+    }
+}
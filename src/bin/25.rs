@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+
+use advent_of_code::error::InputError;
+use advent_of_code::graph::bfs;
+use advent_of_code::verbose;
+
+advent_of_code::solution!(25);
+
+const WIRES_TO_CUT: usize = 3;
+
+/// The components as an undirected graph, addressed by index.
+#[derive(Debug)]
+struct Wiring<'a> {
+    names: Vec<&'a str>,
+    adjacency: Vec<Vec<usize>>,
+}
+
+fn parse(input: &str) -> Result<Wiring<'_>, InputError> {
+    let mut names = Vec::new();
+    let mut indices = HashMap::new();
+    let mut adjacency: Vec<Vec<usize>> = Vec::new();
+    let mut index_of = |name| {
+        *indices.entry(name).or_insert_with(|| {
+            names.push(name);
+            adjacency.push(Vec::new());
+            names.len() - 1
+        })
+    };
+
+    let mut wires = Vec::new();
+    for line in input.lines().filter(|line| !line.is_empty()) {
+        let (component, connected) = line
+            .split_once(": ")
+            .ok_or_else(|| InputError::new(input, line, "expected `component: others`"))?;
+        let from = index_of(component);
+        for other in connected.split_whitespace() {
+            wires.push((from, index_of(other)));
+        }
+    }
+    for (a, b) in wires {
+        adjacency[a].push(b);
+        adjacency[b].push(a);
+    }
+
+    Ok(Wiring { names, adjacency })
+}
+
+impl Wiring<'_> {
+    /// Size of the `source` side of a minimum cut between `source` and `sink`, if that cut has at
+    /// most `limit` wires.
+    ///
+    /// Every wire carries one unit of flow in either direction. Augmenting paths are found
+    /// with BFS until none is left, then the components still reachable from `source` form
+    /// its side of the cut.
+    fn cut_side(&self, source: usize, sink: usize, limit: usize) -> Option<usize> {
+        // Net flow from the first to the second component; the reverse is its negation.
+        let mut flow: HashMap<(usize, usize), i32> = HashMap::new();
+
+        for _ in 0..=limit {
+            let search = bfs(
+                source,
+                |&from| {
+                    let flow = &flow;
+                    self.adjacency[from]
+                        .iter()
+                        .copied()
+                        .filter(move |&to| flow.get(&(from, to)).copied().unwrap_or(0) < 1)
+                },
+                |&node| node == sink,
+            );
+
+            let Some(path) = search.path() else {
+                return Some(search.costs().count());
+            };
+            for pair in path.windows(2) {
+                let (from, to) = (pair[0], pair[1]);
+                *flow.entry((from, to)).or_default() += 1;
+                *flow.entry((to, from)).or_default() -= 1;
+            }
+        }
+
+        None
+    }
+
+    /// Sizes of the two groups left after cutting `wires` wires, if each has several components.
+    fn split(&self, wires: usize) -> Option<(usize, usize)> {
+        // The source lands on one side of the cut, so some other component is on the far side.
+        // Components far from the source are the likeliest, so they are tried first.
+        let source = 0;
+        let search = bfs(
+            source,
+            |&from| self.adjacency[from].iter().copied(),
+            |_| false,
+        );
+        let mut sinks: Vec<(usize, usize)> = search
+            .costs()
+            .filter(|&(&node, _)| node != source)
+            .map(|(&node, distance)| (distance, node))
+            .collect();
+        sinks.sort_unstable_by(|a, b| b.cmp(a));
+
+        sinks.into_iter().find_map(|(_, sink)| {
+            let side = self.cut_side(source, sink, wires)?;
+            let other = self.names.len() - side;
+            verbose!(
+                "cut between {} and {}",
+                self.names[source],
+                self.names[sink]
+            );
+            (side > 1 && other > 1).then_some((side, other))
+        })
+    }
+}
+
+pub fn part_one(input: &str) -> Result<usize, InputError> {
+    let wiring = parse(input)?;
+
+    let (a, b) = wiring.split(WIRES_TO_CUT).ok_or_else(|| {
+        InputError::unlocated("cutting three wires does not split the components into two groups")
+    })?;
+
+    Ok(a * b)
+}
+
+/// There is no second puzzle on the last day.
+pub fn part_two(_input: &str) -> Option<u32> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_example() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let wiring = parse(&input).unwrap();
+
+        assert_eq!(wiring.names.len(), 15);
+        assert_eq!(wiring.adjacency.iter().map(Vec::len).sum::<usize>(), 2 * 33);
+    }
+
+    #[test]
+    fn test_cut_side() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let wiring = parse(&input).unwrap();
+        let index = |name| wiring.names.iter().position(|&n| n == name).unwrap();
+
+        // Three wires separate `jqt` and `cmg`, while `jqt` and `ntq` are on the same side.
+        assert_eq!(wiring.cut_side(index("jqt"), index("cmg"), 3), Some(6));
+        assert_eq!(wiring.cut_side(index("jqt"), index("ntq"), 3), None);
+    }
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Ok(54));
+    }
+
+    #[test]
+    fn test_split_single_component() {
+        // Cutting the one wire to `f` leaves it on its own.
+        let input = "a: b c d e\nb: c d e\nc: d e\nd: e\ne: f\n";
+        let wiring = parse(input).unwrap();
+
+        assert_eq!(wiring.split(WIRES_TO_CUT), None);
+        assert!(part_one(input).is_err());
+    }
+
+    #[test]
+    fn test_split_far_side_of_source() {
+        // A long ring where every component is wired to the next two, so the components farthest
+        // from `r00` are on its own side, joined to a clique of eight by three wires.
+        let mut input = String::new();
+        for i in 0..40 {
+            input += &format!("r{i:02}: r{:02} r{:02}\n", (i + 1) % 40, (i + 2) % 40);
+        }
+        for i in 0..8 {
+            let others: Vec<String> = (i + 1..8).map(|j| format!("k{j}")).collect();
+            if !others.is_empty() {
+                input += &format!("k{i}: {}\n", others.join(" "));
+            }
+        }
+        input += "r00: k0\nr10: k1\nr20: k2\n";
+
+        assert_eq!(part_one(&input), Ok(320));
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, None);
+    }
+}
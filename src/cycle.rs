@@ -0,0 +1,195 @@
+//! Cycle detection for repeated simulations, to jump ahead to far-future steps.
+//!
+//! A simulation is a start state and a `step` function. Because states are finite, the sequence
+//! `start, step(start), ...` eventually repeats: after a prefix of `prefix` states it cycles with
+//! period `length`.
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Where a sequence of states starts repeating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// Number of states before the first state of the cycle.
+    pub prefix: usize,
+    /// Number of states in the cycle.
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The first step (below `prefix + length`) with the same state as step `n`.
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.prefix {
+            n
+        } else {
+            self.prefix + (n - self.prefix) % self.length
+        }
+    }
+
+    /// The state at step `n`, by replaying at most `prefix + length` steps from `start`.
+    pub fn state_at<S>(&self, start: S, mut step: impl FnMut(&S) -> S, n: usize) -> S {
+        (0..self.equivalent_step(n)).fold(start, |state, _| step(&state))
+    }
+}
+
+/// Detects the cycle with Floyd's tortoise and hare, keeping only a constant number of states.
+pub fn floyd<S: Clone + Eq>(start: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut tortoise = step(&start);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    let mut prefix = 0;
+    tortoise = start;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+
+    Cycle { prefix, length }
+}
+
+/// Detects the cycle with Brent's algorithm, which needs fewer steps than [`floyd`].
+pub fn brent<S: Clone + Eq>(start: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    let mut tortoise = start.clone();
+    let mut hare = (0..length).fold(start, |state, _| step(&state));
+    let mut prefix = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+
+    Cycle { prefix, length }
+}
+
+/// The states of a simulation up to the end of its first cycle, found by [`find`].
+#[derive(Debug, Clone)]
+pub struct History<S> {
+    pub cycle: Cycle,
+    states: Vec<S>,
+}
+
+impl<S> History<S> {
+    /// The state at step `n`, which can be arbitrarily far in the future.
+    pub fn state_at(&self, n: usize) -> &S {
+        &self.states[self.cycle.equivalent_step(n)]
+    }
+
+    /// The states of the prefix followed by one pass through the cycle.
+    pub fn states(&self) -> &[S] {
+        &self.states
+    }
+
+    /// The states of one pass through the cycle.
+    pub fn cycle_states(&self) -> &[S] {
+        &self.states[self.cycle.prefix..]
+    }
+}
+
+/// Detects the cycle by remembering every state, so any step can be looked up afterwards.
+pub fn find<S: Clone + Eq + Hash>(start: S, mut step: impl FnMut(&S) -> S) -> History<S> {
+    let mut seen = HashMap::new();
+    let mut states = Vec::new();
+    let mut state = start;
+    loop {
+        if let Some(&prefix) = seen.get(&state) {
+            let length = states.len() - prefix;
+            return History {
+                cycle: Cycle { prefix, length },
+                states,
+            };
+        }
+        seen.insert(state.clone(), states.len());
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `x -> x² + 1 mod 255` from 3: 3, 10, 101, 2, 5, 26, 167, 95, 101, ... (prefix 2, length 6).
+    fn step(x: &u32) -> u32 {
+        (x * x + 1) % 255
+    }
+
+    #[test]
+    fn test_algorithms_agree() {
+        let expected = Cycle {
+            prefix: 2,
+            length: 6,
+        };
+
+        assert_eq!(floyd(3, step), expected);
+        assert_eq!(brent(3, step), expected);
+        assert_eq!(find(3, step).cycle, expected);
+    }
+
+    #[test]
+    fn test_pure_cycle() {
+        let step = |x: &u8| (x + 1) % 5;
+        let expected = Cycle {
+            prefix: 0,
+            length: 5,
+        };
+
+        assert_eq!(floyd(0, step), expected);
+        assert_eq!(brent(0, step), expected);
+        assert_eq!(find(0, step).cycle, expected);
+    }
+
+    #[test]
+    fn test_fixed_point() {
+        let step = |x: &u32| (x / 2).max(1);
+
+        assert_eq!(
+            brent(40, step),
+            Cycle {
+                prefix: 5,
+                length: 1
+            }
+        );
+        assert_eq!(find(40, step).state_at(1_000_000_000), &1);
+    }
+
+    #[test]
+    fn test_state_at_far_future() {
+        let history = find(3, step);
+        let mut state = 3;
+        for n in 0..100 {
+            assert_eq!(history.state_at(n), &state, "step {n}");
+            assert_eq!(history.cycle.state_at(3, step, n), state, "step {n}");
+            state = step(&state);
+        }
+
+        assert_eq!(history.cycle_states(), [101, 2, 5, 26, 167, 95]);
+        assert_eq!(history.state_at(1_000_000_004), &101);
+    }
+}
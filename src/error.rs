@@ -0,0 +1,283 @@
+//! Errors pointing at a line and column of the puzzle input, rendered with a caret diagnostic:
+//!
+//! ```text
+//! error: invalid card `X`
+//!  --> 2:4
+//!   |
+//! 2 | T55X5 684
+//!   |    ^
+//!   = while parsing hand `T55X5`
+//! ```
+//!
+//! Errors about the input as a whole, like a missing start tile, have no location and are
+//! rendered without the caret.
+use std::fmt::Display;
+use std::ops::Range;
+
+use crate::parse;
+
+/// An error in the puzzle input, located by line and column unless it is about the whole input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputError {
+    message: String,
+    location: Option<Location>,
+    context: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Location {
+    line: usize,
+    column: usize,
+    width: usize,
+    source_line: String,
+}
+
+impl InputError {
+    /// Creates an error for the slice `at`, which should be a slice of `input`.
+    pub fn new(input: &str, at: &str, message: impl Display) -> Self {
+        Self::at_span(input, address_range(at), message)
+    }
+
+    /// Creates an error about the input as a whole, with no line or column to point at.
+    pub fn unlocated(message: impl Display) -> Self {
+        InputError {
+            message: message.to_string(),
+            location: None,
+            context: Vec::new(),
+        }
+    }
+
+    fn at_span(input: &str, span: Range<usize>, message: impl Display) -> Self {
+        let (start, end) = match offset_in(input, &span) {
+            Some(start) => (start, start + span.len()),
+            None => (0, 0),
+        };
+
+        let line_start = input[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[start..].find('\n').map_or(input.len(), |i| start + i);
+        let source_line = input[line_start..line_end].trim_end_matches('\r');
+
+        let end = end.min(line_start + source_line.len()).max(start);
+
+        InputError {
+            message: message.to_string(),
+            location: Some(Location {
+                line: input[..start].matches('\n').count() + 1,
+                column: input[line_start..start].chars().count() + 1,
+                width: input[start..end].chars().count().max(1),
+                source_line: source_line.to_owned(),
+            }),
+            context: Vec::new(),
+        }
+    }
+
+    /// Adds a note on what was being done when the error occurred, e.g. "while parsing workflow `px`".
+    #[must_use]
+    pub fn context(mut self, context: impl Display) -> Self {
+        self.context.push(context.to_string());
+        self
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The line and column (in characters) of the error, both starting at 1.
+    pub fn location(&self) -> Option<(usize, usize)> {
+        self.location
+            .as_ref()
+            .map(|location| (location.line, location.column))
+    }
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "error: {}", self.message)?;
+
+        let gutter = match &self.location {
+            Some(location) => {
+                let gutter = " ".repeat(location.line.to_string().len());
+                write!(f, "\n{gutter}--> {}:{}", location.line, location.column)?;
+                write!(f, "\n{gutter} |")?;
+                write!(f, "\n{} | {}", location.line, location.source_line)?;
+                write!(
+                    f,
+                    "\n{gutter} | {}{}",
+                    " ".repeat(location.column - 1),
+                    "^".repeat(location.width)
+                )?;
+                gutter
+            }
+            None => String::new(),
+        };
+        for context in &self.context {
+            write!(f, "\n{gutter} = {context}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// Attaching context to results carrying an [`InputError`].
+pub trait Context<T> {
+    fn context(self, context: impl Display) -> Result<T, InputError>;
+
+    fn with_context<D: Display>(self, context: impl FnOnce() -> D) -> Result<T, InputError>;
+}
+
+impl<T> Context<T> for Result<T, InputError> {
+    fn context(self, context: impl Display) -> Result<T, InputError> {
+        self.map_err(|e| e.context(context))
+    }
+
+    fn with_context<D: Display>(self, context: impl FnOnce() -> D) -> Result<T, InputError> {
+        self.map_err(|e| e.context(context()))
+    }
+}
+
+/// An error of kind `E` attached to the slice of the input where it occurred.
+///
+/// Only the address range of that slice is kept, so the error does not borrow the input (and can
+/// be the `Err` of a [`std::str::FromStr`] impl). [`Spanned::locate`] resolves it against the
+/// full input to find the line and column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spanned<E> {
+    pub kind: E,
+    span: Range<usize>,
+}
+
+impl<E> Spanned<E> {
+    pub fn new(kind: E, at: &str) -> Self {
+        Spanned {
+            kind,
+            span: address_range(at),
+        }
+    }
+}
+
+impl<E: Display> Spanned<E> {
+    /// Resolves the error against `input`, which must contain the slice the error was created with.
+    pub fn locate(&self, input: &str) -> InputError {
+        InputError::at_span(input, self.span.clone(), &self.kind)
+    }
+}
+
+/// Attaches an error kind to the slice of the input where it occurred.
+pub trait At: Sized {
+    fn at(self, at: &str) -> Spanned<Self> {
+        Spanned::new(self, at)
+    }
+}
+
+impl<E> At for E {}
+
+impl From<parse::ParseError<'_>> for Spanned<parse::ErrorKind> {
+    fn from(error: parse::ParseError<'_>) -> Self {
+        Spanned::new(error.kind, error.at)
+    }
+}
+
+impl parse::ParseError<'_> {
+    /// Resolves the error against `input`, which must contain the slice the error points at.
+    pub fn locate(&self, input: &str) -> InputError {
+        InputError::new(input, self.at, self)
+    }
+}
+
+fn address_range(s: &str) -> Range<usize> {
+    let start = s.as_ptr() as usize;
+    start..start + s.len()
+}
+
+fn offset_in(input: &str, span: &Range<usize>) -> Option<usize> {
+    let input_range = address_range(input);
+    if input_range.start <= span.start && span.end <= input_range.end {
+        Some(span.start - input_range.start)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "32T3K 765\nT55X5 684\nKK677 28\n";
+
+    #[test]
+    fn test_locate_line_and_column() {
+        let error = InputError::new(INPUT, &INPUT[13..14], "invalid card `X`");
+
+        assert_eq!(error.location(), Some((2, 4)));
+        assert_eq!(
+            error.to_string(),
+            "error: invalid card `X`\n --> 2:4\n  |\n2 | T55X5 684\n  |    ^"
+        );
+    }
+
+    #[test]
+    fn test_width_and_context() {
+        let line = INPUT.lines().nth(2).unwrap();
+        let error =
+            InputError::new(INPUT, &line[6..], "bid too low").context("while parsing hand `KK677`");
+
+        assert_eq!(
+            error.to_string(),
+            "error: bid too low\n --> 3:7\n  |\n3 | KK677 28\n  |       ^^\n  = while parsing hand `KK677`"
+        );
+    }
+
+    #[test]
+    fn test_spanned_from_sub_slice() {
+        let line = INPUT.lines().nth(1).unwrap();
+        let spanned = "invalid card".at(&line[3..4]);
+
+        let error = spanned.locate(INPUT);
+        assert_eq!(error.location(), Some((2, 4)));
+    }
+
+    #[test]
+    fn test_empty_slice_at_end_of_line() {
+        let line = INPUT.lines().next().unwrap();
+        let error = InputError::new(INPUT, &line[line.len()..], "expected `}`");
+
+        assert_eq!(error.location(), Some((1, 10)));
+        assert!(error.to_string().ends_with("1 | 32T3K 765\n  |          ^"));
+    }
+
+    #[test]
+    fn test_slice_of_other_string() {
+        let error = InputError::new(INPUT, "elsewhere", "lost");
+
+        assert_eq!(error.location(), Some((1, 1)));
+    }
+
+    #[test]
+    fn test_context_trait() {
+        let result: Result<(), InputError> = Err(InputError::new(INPUT, INPUT, "bad"));
+        let error = result.with_context(|| "while parsing").unwrap_err();
+
+        assert!(error.to_string().ends_with("  = while parsing"));
+    }
+
+    #[test]
+    fn test_unlocated() {
+        let error = InputError::unlocated("expected a start").context("while parsing the map");
+
+        assert_eq!(error.location(), None);
+        assert_eq!(
+            error.to_string(),
+            "error: expected a start\n = while parsing the map"
+        );
+    }
+
+    #[test]
+    fn test_parse_error_locate() {
+        let line = INPUT.lines().nth(1).unwrap();
+        let error = parse::literal(line, "Hand:").unwrap_err().locate(INPUT);
+
+        assert_eq!(error.message(), "expected `Hand:`");
+        assert_eq!(error.location(), Some((2, 1)));
+    }
+}
@@ -0,0 +1,181 @@
+//! Lattice polygon and distance helpers, generic over the primitive integer types.
+use itertools::Itertools;
+
+use crate::math::gcd;
+use crate::num::{Integer, Signed};
+
+/// An `(x, y)` coordinate, with `y` growing downwards (south) as in the puzzle inputs.
+pub type Point<T> = (T, T);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    /// The `(dx, dy)` of a single step in this direction.
+    pub fn delta<T: Signed>(self) -> Point<T> {
+        match self {
+            Direction::North => (T::ZERO, -T::ONE),
+            Direction::East => (T::ONE, T::ZERO),
+            Direction::South => (T::ZERO, T::ONE),
+            Direction::West => (-T::ONE, T::ZERO),
+        }
+    }
+
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::North => Direction::South,
+            Direction::East => Direction::West,
+            Direction::South => Direction::North,
+            Direction::West => Direction::East,
+        }
+    }
+
+    pub fn turn_left(self) -> Direction {
+        match self {
+            Direction::North => Direction::West,
+            Direction::East => Direction::North,
+            Direction::South => Direction::East,
+            Direction::West => Direction::South,
+        }
+    }
+
+    pub fn turn_right(self) -> Direction {
+        self.turn_left().opposite()
+    }
+}
+
+/// Moves `steps` from `point` in `direction`.
+pub fn step<T: Signed>(point: Point<T>, direction: Direction, steps: T) -> Point<T> {
+    let (dx, dy) = direction.delta::<T>();
+    (point.0 + dx * steps, point.1 + dy * steps)
+}
+
+/// Twice the (unsigned) area of the polygon described by `vertices`, in order.
+///
+/// Staying in the doubled domain keeps the result exact for lattice polygons.
+pub fn double_area<T: Signed>(vertices: &[Point<T>]) -> T {
+    vertices
+        .iter()
+        .circular_tuple_windows()
+        .map(|(&(x0, y0), &(x1, y1))| x0 * y1 - x1 * y0)
+        .sum::<T>()
+        .abs()
+}
+
+/// Area of the polygon described by `vertices` using the shoelace formula, rounded down.
+pub fn shoelace<T: Signed>(vertices: &[Point<T>]) -> T {
+    double_area(vertices) / T::TWO
+}
+
+/// Number of lattice points on the boundary of the polygon described by `vertices`.
+pub fn boundary_points<T: Signed>(vertices: &[Point<T>]) -> T {
+    vertices
+        .iter()
+        .circular_tuple_windows()
+        .map(|(&(x0, y0), &(x1, y1))| gcd((x1 - x0).abs(), (y1 - y0).abs()))
+        .sum()
+}
+
+/// Number of lattice points strictly inside the polygon described by `vertices`, using Pick's theorem.
+pub fn interior_points<T: Signed>(vertices: &[Point<T>]) -> T {
+    (double_area(vertices) - boundary_points(vertices) + T::TWO) / T::TWO
+}
+
+/// Traces a polygon by following `(direction, length)` instructions from `start`.
+///
+/// The returned vertices begin with `start`; a final vertex that returns to `start` is left out.
+pub fn polygon_from_instructions<T: Signed>(
+    start: Point<T>,
+    instructions: impl IntoIterator<Item = (Direction, T)>,
+) -> Vec<Point<T>> {
+    let mut vertices = vec![start];
+    let mut current = start;
+    for (direction, length) in instructions {
+        current = step(current, direction, length);
+        vertices.push(current);
+    }
+    if vertices.len() > 1 && vertices.last() == Some(&start) {
+        vertices.pop();
+    }
+    vertices
+}
+
+/// Manhattan (taxicab) distance between two points.
+pub fn manhattan<T: Integer>(a: Point<T>, b: Point<T>) -> T {
+    a.0.abs_delta(b.0) + a.1.abs_delta(b.1)
+}
+
+/// Chebyshev (king move) distance between two points.
+pub fn chebyshev<T: Integer>(a: Point<T>, b: Point<T>) -> T {
+    std::cmp::max(a.0.abs_delta(b.0), a.1.abs_delta(b.1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shoelace() {
+        let result = shoelace(&[(2, 1), (5, 0), (6, 4), (4, 2), (1, 3)]);
+        assert_eq!(result, 8);
+    }
+
+    #[test]
+    fn test_square_pick() {
+        let square: Vec<Point<i32>> = vec![(0, 0), (4, 0), (4, 4), (0, 4)];
+
+        assert_eq!(shoelace(&square), 16);
+        assert_eq!(boundary_points(&square), 16);
+        assert_eq!(interior_points(&square), 9);
+    }
+
+    #[test]
+    fn test_diagonal_boundary() {
+        let triangle: Vec<Point<i64>> = vec![(0, 0), (4, 2), (0, 2)];
+
+        assert_eq!(boundary_points(&triangle), 2 + 2 + 4);
+        assert_eq!(double_area(&triangle), 8);
+        assert_eq!(interior_points(&triangle), 1);
+    }
+
+    #[test]
+    fn test_polygon_from_instructions() {
+        use Direction::*;
+        let vertices = polygon_from_instructions(
+            (0i128, 0i128),
+            [(East, 3), (South, 2), (West, 3), (North, 2)],
+        );
+
+        assert_eq!(vertices, vec![(0, 0), (3, 0), (3, 2), (0, 2)]);
+        assert_eq!(interior_points(&vertices) + boundary_points(&vertices), 12);
+    }
+
+    #[test]
+    fn test_huge_coordinates() {
+        use Direction::*;
+        let side = 10_000_000_000i128;
+        let vertices =
+            polygon_from_instructions((0, 0), [(East, side), (South, side), (West, side)]);
+
+        assert_eq!(shoelace(&vertices), side * side);
+    }
+
+    #[test]
+    fn test_distances() {
+        assert_eq!(manhattan((1usize, 6usize), (5, 11)), 9);
+        assert_eq!(manhattan((-3i32, 2), (4, -2)), 11);
+        assert_eq!(chebyshev((-3i32, 2), (4, -2)), 7);
+    }
+}
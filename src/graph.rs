@@ -0,0 +1,440 @@
+//! Generic breadth-first, Dijkstra and A* searches with path reconstruction.
+//!
+//! Nodes can be anything hashable, like grid points or composite states such as
+//! `(position, direction, run length)`. The searches record what they learn in a [`NodeMap`]:
+//! a [`HashMap`] by default, or a [`Dense`] vector for nodes that can be turned into an index.
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use crate::num::Integer;
+
+/// What a search recorded about a reached node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Visit<N, C> {
+    pub cost: C,
+    pub predecessor: Option<N>,
+}
+
+/// Storage for per-node search data.
+pub trait NodeMap<N, V> {
+    fn get(&self, node: &N) -> Option<&V>;
+    fn insert(&mut self, node: N, value: V);
+}
+
+impl<N: Eq + Hash, V> NodeMap<N, V> for HashMap<N, V> {
+    fn get(&self, node: &N) -> Option<&V> {
+        HashMap::get(self, node)
+    }
+
+    fn insert(&mut self, node: N, value: V) {
+        HashMap::insert(self, node, value);
+    }
+}
+
+/// A [`NodeMap`] backed by a vector, for nodes that map onto `0..len` (e.g. with [`crate::grid::Grid::index_of`]).
+pub struct Dense<V, F> {
+    values: Vec<Option<V>>,
+    index: F,
+}
+
+impl<V, F> Dense<V, F> {
+    pub fn new(len: usize, index: F) -> Self {
+        Dense {
+            values: std::iter::repeat_with(|| None).take(len).collect(),
+            index,
+        }
+    }
+}
+
+impl<N, V, F: Fn(&N) -> usize> NodeMap<N, V> for Dense<V, F> {
+    fn get(&self, node: &N) -> Option<&V> {
+        self.values.get((self.index)(node))?.as_ref()
+    }
+
+    fn insert(&mut self, node: N, value: V) {
+        let index = (self.index)(&node);
+        self.values[index] = Some(value);
+    }
+}
+
+pub type HashVisits<N, C> = HashMap<N, Visit<N, C>>;
+
+/// The outcome of a search: the cost of and predecessor for every reached node, and the goal
+/// (if one was reached).
+pub struct Search<N, C, M = HashVisits<N, C>> {
+    visits: M,
+    goal: Option<N>,
+    cost: PhantomData<C>,
+}
+
+impl<N: Clone, C: Copy, M: NodeMap<N, Visit<N, C>>> Search<N, C, M> {
+    fn new(visits: M) -> Self {
+        Search {
+            visits,
+            goal: None,
+            cost: PhantomData,
+        }
+    }
+
+    /// The cost of the cheapest path to `node`, if it was reached.
+    pub fn cost(&self, node: &N) -> Option<C> {
+        self.visits.get(node).map(|v| v.cost)
+    }
+
+    pub fn goal(&self) -> Option<&N> {
+        self.goal.as_ref()
+    }
+
+    pub fn goal_cost(&self) -> Option<C> {
+        self.cost(self.goal.as_ref()?)
+    }
+
+    /// The cheapest path from the start to `node`, both included.
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        let mut path = vec![node.clone()];
+        let mut current = self.visits.get(node)?;
+        while let Some(predecessor) = &current.predecessor {
+            path.push(predecessor.clone());
+            current = self.visits.get(predecessor)?;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// The cheapest path from the start to the goal, both included.
+    pub fn path(&self) -> Option<Vec<N>> {
+        self.path_to(self.goal.as_ref()?)
+    }
+
+    pub fn into_visits(self) -> M {
+        self.visits
+    }
+}
+
+impl<N, C: Copy> Search<N, C, HashVisits<N, C>> {
+    /// Every reached node with the cost of reaching it.
+    pub fn costs(&self) -> impl Iterator<Item = (&N, C)> {
+        self.visits.iter().map(|(n, v)| (n, v.cost))
+    }
+}
+
+/// Breadth-first search from `start` until `is_goal` matches (use `|_| false` to explore everything).
+pub fn bfs<N, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, usize>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    bfs_in(HashMap::new(), start, neighbours, is_goal)
+}
+
+/// [`bfs`] recording into the given `visits`.
+pub fn bfs_in<N, I, M>(
+    visits: M,
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, usize, M>
+where
+    N: Clone,
+    I: IntoIterator<Item = N>,
+    M: NodeMap<N, Visit<N, usize>>,
+{
+    let mut search = Search::new(visits);
+    search.visits.insert(
+        start.clone(),
+        Visit {
+            cost: 0,
+            predecessor: None,
+        },
+    );
+
+    let mut queue = VecDeque::from([(start, 0)]);
+    while let Some((node, cost)) = queue.pop_front() {
+        if is_goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+        for next in neighbours(&node) {
+            if search.visits.get(&next).is_none() {
+                search.visits.insert(
+                    next.clone(),
+                    Visit {
+                        cost: cost + 1,
+                        predecessor: Some(node.clone()),
+                    },
+                );
+                queue.push_back((next, cost + 1));
+            }
+        }
+    }
+
+    search
+}
+
+/// Dijkstra's algorithm from `start` until `is_goal` matches; `neighbours` yields `(node, edge cost)`.
+pub fn dijkstra<N, C, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Integer,
+    I: IntoIterator<Item = (N, C)>,
+{
+    dijkstra_in(HashMap::new(), start, neighbours, is_goal)
+}
+
+/// [`dijkstra`] recording into the given `visits`.
+pub fn dijkstra_in<N, C, I, M>(
+    visits: M,
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, C, M>
+where
+    N: Clone,
+    C: Integer,
+    I: IntoIterator<Item = (N, C)>,
+    M: NodeMap<N, Visit<N, C>>,
+{
+    astar_in(visits, start, neighbours, |_| C::ZERO, is_goal)
+}
+
+/// A* search from `start` until `is_goal` matches.
+///
+/// `heuristic` must never overestimate the remaining cost to a goal for the result to be optimal.
+pub fn astar<N, C, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    heuristic: impl FnMut(&N) -> C,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Integer,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar_in(HashMap::new(), start, neighbours, heuristic, is_goal)
+}
+
+/// [`astar`] recording into the given `visits`.
+pub fn astar_in<N, C, I, M>(
+    visits: M,
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, C, M>
+where
+    N: Clone,
+    C: Integer,
+    I: IntoIterator<Item = (N, C)>,
+    M: NodeMap<N, Visit<N, C>>,
+{
+    let mut search = Search::new(visits);
+    search.visits.insert(
+        start.clone(),
+        Visit {
+            cost: C::ZERO,
+            predecessor: None,
+        },
+    );
+
+    let mut frontier = BinaryHeap::new();
+    frontier.push(Frontier {
+        estimate: heuristic(&start),
+        cost: C::ZERO,
+        node: start,
+    });
+
+    while let Some(Frontier { cost, node, .. }) = frontier.pop() {
+        if search.cost(&node).is_some_and(|best| cost > best) {
+            continue;
+        }
+        if is_goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+        for (next, edge) in neighbours(&node) {
+            let next_cost = cost + edge;
+            if search.cost(&next).is_some_and(|best| best <= next_cost) {
+                continue;
+            }
+            search.visits.insert(
+                next.clone(),
+                Visit {
+                    cost: next_cost,
+                    predecessor: Some(node.clone()),
+                },
+            );
+            frontier.push(Frontier {
+                estimate: next_cost + heuristic(&next),
+                cost: next_cost,
+                node: next,
+            });
+        }
+    }
+
+    search
+}
+
+/// A queued node, ordered so that the [`BinaryHeap`] pops the lowest estimate first.
+struct Frontier<N, C> {
+    estimate: C,
+    cost: C,
+    node: N,
+}
+
+impl<N, C: Ord> PartialEq for Frontier<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<N, C: Ord> Eq for Frontier<N, C> {}
+
+impl<N, C: Ord> PartialOrd for Frontier<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> Ord for Frontier<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .estimate
+            .cmp(&self.estimate)
+            .then_with(|| self.cost.cmp(&other.cost))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::{manhattan, Direction};
+    use crate::grid::{Grid, GridPoint};
+
+    const MAZE: &str = "\
+S.#.....
+.##.###.
+....#...
+.####.#E
+......#.
+";
+
+    fn maze() -> Grid<bool> {
+        Grid::parse(MAZE, |c| Some(c != '#')).unwrap()
+    }
+
+    fn open_neighbours(grid: &Grid<bool>, p: GridPoint) -> Vec<GridPoint> {
+        grid.neighbours(p)
+            .filter(|&(_, n)| grid[n])
+            .map(|(_, n)| n)
+            .collect()
+    }
+
+    #[test]
+    fn test_bfs_path() {
+        let grid = maze();
+        let goal = (7, 3);
+        let search = bfs((0, 0), |&p| open_neighbours(&grid, p), |&p| p == goal);
+
+        assert_eq!(search.goal(), Some(&goal));
+        assert_eq!(search.goal_cost(), Some(14));
+
+        let path = search.path().unwrap();
+        assert_eq!(path.len(), 15);
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert!(path.windows(2).all(|w| manhattan(w[0], w[1]) == 1));
+    }
+
+    #[test]
+    fn test_bfs_distance_map() {
+        let grid = maze();
+        let search = bfs((0, 0), |&p| open_neighbours(&grid, p), |_| false);
+
+        assert_eq!(search.goal(), None);
+        assert_eq!(search.cost(&(1, 0)), Some(1));
+        assert_eq!(search.cost(&(2, 0)), None);
+        assert_eq!(search.costs().map(|(_, c)| c).max(), Some(15));
+    }
+
+    #[test]
+    fn test_bfs_dense() {
+        let grid = maze();
+        let visits = Dense::new(grid.len(), |p: &GridPoint| grid.index_of(*p).unwrap());
+        let search = bfs_in(
+            visits,
+            (0, 0),
+            |&p| open_neighbours(&grid, p),
+            |&p| p == (7, 3),
+        );
+
+        assert_eq!(search.goal_cost(), Some(14));
+        assert_eq!(search.path().map(|p| p.len()), Some(15));
+    }
+
+    #[test]
+    fn test_dijkstra_weighted() {
+        let edges: HashMap<char, Vec<(char, u32)>> = HashMap::from([
+            ('a', vec![('b', 7), ('c', 9), ('f', 14)]),
+            ('b', vec![('a', 7), ('c', 10), ('d', 15)]),
+            ('c', vec![('a', 9), ('b', 10), ('d', 11), ('f', 2)]),
+            ('d', vec![('b', 15), ('c', 11), ('e', 6)]),
+            ('e', vec![('d', 6), ('f', 9)]),
+            ('f', vec![('a', 14), ('c', 2), ('e', 9)]),
+        ]);
+        let search = dijkstra('a', |n| edges[n].clone(), |&n| n == 'e');
+
+        assert_eq!(search.goal_cost(), Some(20));
+        assert_eq!(search.path(), Some(vec!['a', 'c', 'f', 'e']));
+    }
+
+    #[test]
+    fn test_unreachable_goal() {
+        let search = dijkstra(0u8, |&n| (n < 3).then_some((n + 1, 1u32)), |&n| n == 5);
+
+        assert_eq!(search.goal(), None);
+        assert_eq!(search.path(), None);
+        assert_eq!(search.cost(&3), Some(3));
+    }
+
+    #[test]
+    fn test_astar_composite_state() {
+        // Walk the maze without ever going straight for more than four steps, turning costs extra.
+        let grid = maze();
+        let goal = (7, 3);
+        let start = ((0, 0), Direction::East, 0u8);
+        let neighbours = |&(p, d, run): &(GridPoint, Direction, u8)| {
+            grid.neighbours(p)
+                .filter(|&(nd, n)| grid[n] && nd != d.opposite())
+                .filter(|&(nd, _)| nd != d || run < 4)
+                .map(|(nd, n)| {
+                    let run = if nd == d { run + 1 } else { 1 };
+                    let cost = if nd == d { 1 } else { 2 };
+                    ((n, nd, run), cost)
+                })
+                .collect::<Vec<_>>()
+        };
+        let search = astar(
+            start,
+            &neighbours,
+            |&(p, _, _)| manhattan(p, goal) as u32,
+            |&(p, _, _)| p == goal,
+        );
+
+        let path = search.path().unwrap();
+        assert_eq!(path.last().map(|s| s.0), Some(goal));
+        assert!(path.windows(3).all(|w| !(w[1].2 == 4 && w[2].1 == w[1].1)));
+
+        let plain = dijkstra(start, &neighbours, |&(p, _, _)| p == goal);
+        assert_eq!(search.goal_cost(), plain.goal_cost());
+    }
+}
@@ -0,0 +1,238 @@
+//! A rectangular grid of cells, addressed by signed [`Point`]s so that stepping off an edge is
+//! just a failed lookup.
+use std::fmt::Display;
+use std::ops::{Index, IndexMut};
+
+use crate::error::{At, InputError, Spanned};
+use crate::geometry::{step, Direction, Point};
+
+pub type GridPoint = Point<isize>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    UnexpectedChar(char),
+    InvalidLineLength { expected: usize, found: usize },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::UnexpectedChar(c) => write!(f, "unexpected character `{c}`"),
+            ParseError::InvalidLineLength { expected, found } => {
+                write!(f, "expected line of length {expected}, found {found}")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid from row-major `cells`.
+    ///
+    /// # Panics
+    /// If `cells` does not hold exactly `width * height` values.
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(cells.len(), width * height, "cells should fill the grid");
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid::new(width, height, vec![value; width * height])
+    }
+
+    /// Parses one cell per character, failing on characters `cell` does not accept or ragged lines.
+    pub fn parse(input: &str, cell: impl FnMut(char) -> Option<T>) -> Result<Self, InputError> {
+        Grid::parse_spanned(input, cell).map_err(|e| e.locate(input))
+    }
+
+    /// Like [`Grid::parse`], for `s` being part of a larger input the error is located against.
+    pub fn parse_spanned(
+        s: &str,
+        mut cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, Spanned<ParseError>> {
+        let mut lines = s.lines().filter(|line| !line.is_empty()).peekable();
+        let width = lines.peek().map_or(0, |line| line.chars().count());
+
+        let mut cells = Vec::new();
+        let mut height = 0;
+        for line in lines {
+            let length = line.chars().count();
+            if length != width {
+                return Err(ParseError::InvalidLineLength {
+                    expected: width,
+                    found: length,
+                }
+                .at(line));
+            }
+            for (i, c) in line.char_indices() {
+                let value = cell(c)
+                    .ok_or_else(|| ParseError::UnexpectedChar(c).at(&line[i..i + c.len_utf8()]))?;
+                cells.push(value);
+            }
+            height += 1;
+        }
+
+        Ok(Grid::new(width, height, cells))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Number of cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, (x, y): GridPoint) -> bool {
+        (0..self.width as isize).contains(&x) && (0..self.height as isize).contains(&y)
+    }
+
+    /// The row-major index of `point`, if it is inside the grid.
+    pub fn index_of(&self, point: GridPoint) -> Option<usize> {
+        self.contains(point)
+            .then(|| point.1 as usize * self.width + point.0 as usize)
+    }
+
+    /// The point at row-major `index`.
+    pub fn point_of(&self, index: usize) -> GridPoint {
+        ((index % self.width) as isize, (index / self.width) as isize)
+    }
+
+    pub fn get(&self, point: GridPoint) -> Option<&T> {
+        self.index_of(point).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, point: GridPoint) -> Option<&mut T> {
+        self.index_of(point).map(|i| &mut self.cells[i])
+    }
+
+    /// All points, row by row.
+    pub fn points(&self) -> impl Iterator<Item = GridPoint> + '_ {
+        (0..self.cells.len()).map(|i| self.point_of(i))
+    }
+
+    /// All points together with their cell, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (GridPoint, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| (self.point_of(i), cell))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// The first point whose cell matches `pred`.
+    pub fn find(&self, pred: impl Fn(&T) -> bool) -> Option<GridPoint> {
+        self.cells.iter().position(pred).map(|i| self.point_of(i))
+    }
+
+    /// The orthogonal neighbours of `point` that are inside the grid, with the direction to them.
+    pub fn neighbours(
+        &self,
+        point: GridPoint,
+    ) -> impl Iterator<Item = (Direction, GridPoint)> + '_ {
+        Direction::ALL
+            .into_iter()
+            .map(move |d| (d, step(point, d, 1)))
+            .filter(|&(_, p)| self.contains(p))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::new(self.width, self.height, self.cells.iter().map(f).collect())
+    }
+
+    /// Renders the grid one character per cell, e.g. for printing intermediate states.
+    pub fn render(&self, f: impl Fn(GridPoint, &T) -> char) -> String {
+        let mut output = String::with_capacity((self.width + 1) * self.height);
+        for (i, cell) in self.cells.iter().enumerate() {
+            if i > 0 && i % self.width == 0 {
+                output.push('\n');
+            }
+            output.push(f(self.point_of(i), cell));
+        }
+        output
+    }
+}
+
+impl<T> Index<GridPoint> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: GridPoint) -> &T {
+        self.get(point).expect("point should be inside the grid")
+    }
+}
+
+impl<T> IndexMut<GridPoint> for Grid<T> {
+    fn index_mut(&mut self, point: GridPoint) -> &mut T {
+        self.get_mut(point)
+            .expect("point should be inside the grid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_index() {
+        let grid = Grid::parse("#.#\n..#\n", |c| Some(c == '#')).unwrap();
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert!(grid[(0, 0)]);
+        assert!(!grid[(1, 1)]);
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.get((0, -1)), None);
+        assert_eq!(grid.index_of((2, 1)), Some(5));
+        assert_eq!(grid.point_of(5), (2, 1));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = Grid::parse("..\n.x\n", |c| (c == '.').then_some(())).unwrap_err();
+        assert_eq!(error.location(), Some((2, 2)));
+
+        let error = Grid::parse("..\n...\n", |_| Some(())).unwrap_err();
+        assert_eq!(error.message(), "expected line of length 2, found 3");
+    }
+
+    #[test]
+    fn test_neighbours_at_corner() {
+        let grid = Grid::filled(2, 2, 0u8);
+        let neighbours: Vec<_> = grid.neighbours((0, 0)).collect();
+
+        assert_eq!(
+            neighbours,
+            vec![(Direction::East, (1, 0)), (Direction::South, (0, 1))]
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let grid = Grid::parse("#.\n.#\n", |c| Some(c == '#')).unwrap();
+        assert_eq!(grid.render(|_, &b| if b { 'X' } else { ' ' }), "X \n X");
+    }
+}
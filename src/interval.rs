@@ -0,0 +1,534 @@
+//! Half-open integer intervals, normalized interval sets, range maps and n-dimensional boxes.
+//!
+//! Used for puzzles where individual values are too many to enumerate, so whole ranges have to be
+//! split, intersected or translated at once.
+use std::fmt::Display;
+use std::ops::Range;
+
+use crate::num::Integer;
+
+/// The values `start..end`; empty if `end <= start`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: Integer> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Interval { start, end }
+    }
+
+    /// The `len` values starting at `start`.
+    pub fn from_len(start: T, len: T) -> Self {
+        Interval::new(start, start + len)
+    }
+
+    /// The values `start..=last`.
+    pub fn inclusive(start: T, last: T) -> Self {
+        Interval::new(start, last + T::ONE)
+    }
+
+    /// Number of values, zero when empty.
+    pub fn len(&self) -> T {
+        if self.is_empty() {
+            T::ZERO
+        } else {
+            self.end - self.start
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    /// The overlap of both intervals, if not empty.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let overlap = Interval::new(self.start.max(other.start), self.end.min(other.end));
+        (!overlap.is_empty()).then_some(overlap)
+    }
+
+    /// Splits into the non-empty parts below `at` and from `at` upwards.
+    pub fn split_at(&self, at: T) -> (Option<Self>, Option<Self>) {
+        let below = Interval::new(self.start, self.end.min(at));
+        let above = Interval::new(self.start.max(at), self.end);
+        (
+            (!below.is_empty()).then_some(below),
+            (!above.is_empty()).then_some(above),
+        )
+    }
+}
+
+impl<T> From<Range<T>> for Interval<T> {
+    fn from(range: Range<T>) -> Self {
+        Interval {
+            start: range.start,
+            end: range.end,
+        }
+    }
+}
+
+impl<T: Display> Display for Interval<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+/// A set of values stored as sorted, non-empty, non-overlapping and non-adjacent intervals.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: Integer> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet {
+            intervals: Vec::new(),
+        }
+    }
+
+    /// Builds a set from intervals that are already sorted, disjoint and non-adjacent.
+    fn from_normalized(intervals: Vec<Interval<T>>) -> Self {
+        debug_assert!(intervals.iter().all(|i| !i.is_empty()));
+        debug_assert!(intervals.windows(2).all(|w| w[0].end < w[1].start));
+        IntervalSet { intervals }
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+        let first = self.intervals.partition_point(|i| i.end < interval.start);
+        let last = self.intervals.partition_point(|i| i.start <= interval.end);
+
+        let merged = self.intervals[first..last]
+            .iter()
+            .fold(interval, |merged, i| {
+                Interval::new(merged.start.min(i.start), merged.end.max(i.end))
+            });
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Interval<T>> {
+        self.intervals.iter()
+    }
+
+    /// Number of values in the set.
+    pub fn len(&self) -> T {
+        self.intervals.iter().map(Interval::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let index = self.intervals.partition_point(|i| i.end <= value);
+        self.intervals.get(index).is_some_and(|i| i.contains(value))
+    }
+
+    /// The lowest value, if any.
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(|i| i.start)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for &interval in &other.intervals {
+            union.insert(interval);
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let (mut a, mut b) = (self.intervals.iter(), other.intervals.iter());
+        let (mut x, mut y) = (a.next(), b.next());
+        while let (Some(i), Some(j)) = (x, y) {
+            intervals.extend(i.intersection(j));
+            if i.end < j.end {
+                x = a.next();
+            } else {
+                y = b.next();
+            }
+        }
+        IntervalSet::from_normalized(intervals)
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let mut others = other.intervals.iter().peekable();
+        for &interval in &self.intervals {
+            let mut rest = interval;
+            while let Some(cut) = others.peek() {
+                if cut.end <= rest.start {
+                    others.next();
+                    continue;
+                }
+                if cut.start >= rest.end {
+                    break;
+                }
+                let (below, above) = rest.split_at(cut.start);
+                intervals.extend(below);
+                match above.and_then(|above| above.split_at(cut.end).1) {
+                    Some(above) => {
+                        rest = above;
+                        others.next();
+                    }
+                    None => {
+                        rest = Interval::new(rest.end, rest.end);
+                        break;
+                    }
+                }
+            }
+            if !rest.is_empty() {
+                intervals.push(rest);
+            }
+        }
+        IntervalSet::from_normalized(intervals)
+    }
+
+    /// Splits into the values below `at` and those from `at` upwards.
+    pub fn split_at(&self, at: T) -> (Self, Self) {
+        let (mut below, mut above) = (Vec::new(), Vec::new());
+        for interval in &self.intervals {
+            let (b, a) = interval.split_at(at);
+            below.extend(b);
+            above.extend(a);
+        }
+        (
+            IntervalSet::from_normalized(below),
+            IntervalSet::from_normalized(above),
+        )
+    }
+}
+
+impl<T: Integer> From<Interval<T>> for IntervalSet<T> {
+    fn from(interval: Interval<T>) -> Self {
+        let mut set = IntervalSet::new();
+        set.insert(interval);
+        set
+    }
+}
+
+impl<T: Integer> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut set = IntervalSet::new();
+        for interval in iter {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+/// Translates values in source intervals to destination intervals of the same length, leaving
+/// values outside all sources unchanged.
+///
+/// If sources overlap, the entry inserted first wins.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RangeMap<T> {
+    entries: Vec<(Interval<T>, T)>,
+}
+
+impl<T: Integer> RangeMap<T> {
+    pub fn new() -> Self {
+        RangeMap {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Maps `source` onto the interval of the same length starting at `destination`.
+    pub fn insert(&mut self, source: Interval<T>, destination: T) {
+        self.entries.push((source, destination));
+    }
+
+    pub fn get(&self, value: T) -> T {
+        self.entries
+            .iter()
+            .find(|(source, _)| source.contains(value))
+            .map_or(value, |&(source, destination)| {
+                value - source.start + destination
+            })
+    }
+
+    /// Maps every value of `set`, splitting intervals where they cross source boundaries.
+    pub fn get_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut unmapped = set.clone();
+        let mut mapped = IntervalSet::new();
+        for &(source, destination) in &self.entries {
+            let sources = IntervalSet::from(source);
+            for covered in unmapped.intersection(&sources).iter() {
+                let start = covered.start - source.start + destination;
+                mapped.insert(Interval::from_len(start, covered.len()));
+            }
+            unmapped = unmapped.difference(&sources);
+        }
+        mapped.union(&unmapped)
+    }
+}
+
+impl<T: Integer> FromIterator<(Interval<T>, T)> for RangeMap<T> {
+    fn from_iter<I: IntoIterator<Item = (Interval<T>, T)>>(iter: I) -> Self {
+        RangeMap {
+            entries: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// An axis-aligned box in `N` dimensions, e.g. `x/m/a/s` rating ranges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cuboid<T, const N: usize> {
+    pub axes: [Interval<T>; N],
+}
+
+impl<T: Integer, const N: usize> Cuboid<T, N> {
+    pub fn new(axes: [Interval<T>; N]) -> Self {
+        Cuboid { axes }
+    }
+
+    /// Number of points inside, zero when empty.
+    pub fn volume(&self) -> T {
+        self.axes.iter().map(Interval::len).product()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.axes.iter().any(Interval::is_empty)
+    }
+
+    pub fn contains(&self, point: [T; N]) -> bool {
+        self.axes
+            .iter()
+            .zip(point)
+            .all(|(axis, v)| axis.contains(v))
+    }
+
+    /// The overlap of both boxes, if not empty.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let mut axes = self.axes;
+        for (axis, other) in axes.iter_mut().zip(&other.axes) {
+            *axis = axis.intersection(other)?;
+        }
+        Some(Cuboid { axes })
+    }
+
+    /// Splits along `axis` into the non-empty parts below `at` and from `at` upwards.
+    pub fn split_at(&self, axis: usize, at: T) -> (Option<Self>, Option<Self>) {
+        let (below, above) = self.axes[axis].split_at(at);
+        let with_axis = |interval: Interval<T>| {
+            let mut axes = self.axes;
+            axes[axis] = interval;
+            Cuboid { axes }
+        };
+        (below.map(with_axis), above.map(with_axis))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Values are drawn from `0..UNIVERSE` so sets can be compared as bitmasks.
+    const UNIVERSE: u32 = 128;
+
+    struct XorShift(u64);
+
+    impl XorShift {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: u32) -> u32 {
+            (self.next() % n as u64) as u32
+        }
+
+        fn interval(&mut self) -> Interval<u32> {
+            let start = self.below(UNIVERSE);
+            let end = start + self.below(UNIVERSE - start + 1);
+            Interval::new(start, end)
+        }
+
+        fn set(&mut self) -> (IntervalSet<u32>, u128) {
+            let mut set = IntervalSet::new();
+            let mut mask = 0;
+            for _ in 0..self.below(5) {
+                let interval = self.interval();
+                set.insert(interval);
+                mask |= mask_of(interval);
+            }
+            (set, mask)
+        }
+    }
+
+    fn mask_of(interval: Interval<u32>) -> u128 {
+        (interval.start..interval.end).fold(0, |mask, v| mask | 1 << v)
+    }
+
+    fn set_mask(set: &IntervalSet<u32>) -> u128 {
+        set.iter().fold(0, |mask, &i| mask | mask_of(i))
+    }
+
+    fn assert_normalized(set: &IntervalSet<u32>) {
+        assert!(set.iter().all(|i| !i.is_empty()), "{set:?}");
+        assert!(
+            set.intervals().windows(2).all(|w| w[0].end < w[1].start),
+            "{set:?}"
+        );
+    }
+
+    #[test]
+    fn test_interval_basics() {
+        let interval = Interval::inclusive(1, 4000);
+
+        assert_eq!(interval.len(), 4000);
+        assert!(interval.contains(4000));
+        assert!(!interval.contains(4001));
+        assert_eq!(
+            interval.split_at(1351),
+            (
+                Some(Interval::new(1, 1351)),
+                Some(Interval::new(1351, 4001))
+            )
+        );
+        assert_eq!(interval.split_at(0), (None, Some(interval)));
+        assert_eq!(Interval::new(5, 3).len(), 0);
+        assert_eq!(Interval::from(2..4).intersection(&(4..6).into()), None);
+    }
+
+    #[test]
+    fn test_set_insert_merges_adjacent() {
+        let set: IntervalSet<i32> = [(5..8).into(), (0..2).into(), (2..3).into(), (7..10).into()]
+            .into_iter()
+            .collect();
+
+        assert_eq!(set.intervals(), [Interval::new(0, 3), Interval::new(5, 10)]);
+        assert_eq!(set.len(), 8);
+        assert!(set.contains(9));
+        assert!(!set.contains(3));
+        assert_eq!(set.min(), Some(0));
+    }
+
+    #[test]
+    fn test_set_operations_against_brute_force() {
+        let mut rng = XorShift(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..2000 {
+            let (a, a_mask) = rng.set();
+            let (b, b_mask) = rng.set();
+            assert_eq!(set_mask(&a), a_mask);
+
+            let union = a.union(&b);
+            let intersection = a.intersection(&b);
+            let difference = a.difference(&b);
+            for set in [&union, &intersection, &difference] {
+                assert_normalized(set);
+            }
+            assert_eq!(set_mask(&union), a_mask | b_mask, "{a:?} | {b:?}");
+            assert_eq!(set_mask(&intersection), a_mask & b_mask, "{a:?} & {b:?}");
+            assert_eq!(set_mask(&difference), a_mask & !b_mask, "{a:?} - {b:?}");
+            assert_eq!(a.len(), a_mask.count_ones());
+
+            let at = rng.below(UNIVERSE);
+            let (below, above) = a.split_at(at);
+            let below_at = (1u128 << at) - 1;
+            assert_eq!(set_mask(&below), a_mask & below_at);
+            assert_eq!(set_mask(&above), a_mask & !below_at);
+
+            let value = rng.below(UNIVERSE);
+            assert_eq!(a.contains(value), a_mask & 1 << value != 0);
+        }
+    }
+
+    #[test]
+    fn test_range_map_against_brute_force() {
+        let mut rng = XorShift(0x2545_f491_4f6c_dd1d);
+        for _ in 0..500 {
+            let map: RangeMap<u32> = (0..rng.below(4))
+                .map(|_| (rng.interval(), rng.below(UNIVERSE)))
+                .collect();
+            let (set, mask) = rng.set();
+
+            let expected: std::collections::BTreeSet<u32> = (0..UNIVERSE)
+                .filter(|v| mask & 1 << v != 0)
+                .map(|v| map.get(v))
+                .collect();
+            let mapped = map.get_set(&set);
+
+            assert_normalized(&mapped);
+            let actual: std::collections::BTreeSet<u32> =
+                mapped.iter().flat_map(|i| i.start..i.end).collect();
+            assert_eq!(actual, expected, "{map:?} {set:?}");
+        }
+    }
+
+    #[test]
+    fn test_range_map_seed_example() {
+        let map: RangeMap<u64> = [
+            (Interval::from_len(98, 2), 50),
+            (Interval::from_len(50, 48), 52),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(map.get(79), 81);
+        assert_eq!(map.get(99), 51);
+        assert_eq!(map.get(13), 13);
+
+        let set = IntervalSet::from(Interval::from_len(90, 20));
+        assert_eq!(
+            map.get_set(&set).intervals(),
+            [
+                Interval::new(50, 52),
+                Interval::new(92, 100),
+                Interval::new(100, 110)
+            ]
+            .into_iter()
+            .collect::<IntervalSet<_>>()
+            .intervals()
+        );
+    }
+
+    #[test]
+    fn test_cuboid_against_brute_force() {
+        let mut rng = XorShift(0xdead_beef_cafe_f00d);
+        let small = |rng: &mut XorShift| {
+            let start = rng.below(8);
+            Interval::new(start, start + rng.below(9 - start))
+        };
+        for _ in 0..300 {
+            let a = Cuboid::new([small(&mut rng), small(&mut rng), small(&mut rng)]);
+            let b = Cuboid::new([small(&mut rng), small(&mut rng), small(&mut rng)]);
+            let points =
+                || (0..8).flat_map(|x| (0..8).flat_map(move |y| (0..8).map(move |z| [x, y, z])));
+
+            assert_eq!(
+                a.volume(),
+                points().filter(|&p| a.contains(p)).count() as u32
+            );
+            let overlap = points().filter(|&p| a.contains(p) && b.contains(p)).count() as u32;
+            assert_eq!(a.intersection(&b).map_or(0, |c| c.volume()), overlap);
+
+            let (axis, at) = (rng.below(3) as usize, rng.below(9));
+            let (below, above) = a.split_at(axis, at);
+            let volume = |c: Option<Cuboid<u32, 3>>| c.map_or(0, |c| c.volume());
+            assert_eq!(volume(below) + volume(above), a.volume());
+            assert!(below.is_none_or(|c| c.axes[axis].end <= at));
+        }
+    }
+
+    #[test]
+    fn test_cuboid_xmas_volume() {
+        let all = Cuboid::new([Interval::inclusive(1u64, 4000); 4]);
+        assert_eq!(all.volume(), 256_000_000_000_000);
+        assert!(all.contains([787, 2655, 1222, 2876]));
+    }
+}
@@ -1,4 +1,14 @@
+pub mod cycle;
 mod day;
+pub mod error;
+pub mod geometry;
+pub mod graph;
+pub mod grid;
+pub mod interval;
+pub mod math;
+pub mod memo;
+pub mod num;
+pub mod parse;
 pub mod template;
 
 pub use day::*;
@@ -20,6 +20,8 @@ mod args {
             day: Day,
             release: bool,
             time: bool,
+            verbose: bool,
+            params: Vec<String>,
             submit: Option<u8>,
         },
         All {
@@ -50,6 +52,8 @@ mod args {
                 release: args.contains("--release"),
                 submit: args.opt_value_from_str("--submit")?,
                 time: args.contains("--time"),
+                verbose: args.contains("--verbose"),
+                params: args.values_from_str("--param")?,
             },
             Some(x) => {
                 eprintln!("Unknown command: {x}");
@@ -85,8 +89,10 @@ fn main() {
                 day,
                 release,
                 time,
+                verbose,
+                params,
                 submit,
-            } => solve::handle(day, release, time, submit),
+            } => solve::handle(day, release, time, verbose, &params, submit),
         },
     };
 }
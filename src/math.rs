@@ -0,0 +1,217 @@
+//! Number theory helpers: gcd/lcm, extended Euclid, modular inverses and the Chinese Remainder Theorem.
+//!
+//! Anything that can overflow returns an [`Option`] instead of silently wrapping.
+use std::borrow::Borrow;
+
+use crate::num::{Integer, Signed};
+
+/// Greatest common divisor, always non-negative. `gcd(0, 0)` is `0`.
+///
+/// # Panics
+/// If the result is not representable, which can only happen for signed `T::MIN` inputs.
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    let (mut a, mut b) = (a, b);
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    if a < T::ZERO {
+        a.checked_neg().expect("gcd of T::MIN is not representable")
+    } else {
+        a
+    }
+}
+
+/// Least common multiple, always non-negative. Returns [`None`] on overflow.
+pub fn lcm<T: Integer>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+    let result = (a / gcd(a, b)).checked_mul(b)?;
+    if result < T::ZERO {
+        result.checked_neg()
+    } else {
+        Some(result)
+    }
+}
+
+/// Greatest common divisor of all `nums`. The gcd of nothing is `0`.
+pub fn gcd_all<T: Integer>(nums: impl IntoIterator<Item = impl Borrow<T>>) -> T {
+    nums.into_iter()
+        .fold(T::ZERO, |acc, n| gcd(acc, *n.borrow()))
+}
+
+/// Least common multiple of all `nums`. The lcm of nothing is `1`. Returns [`None`] on overflow.
+pub fn lcm_all<T: Integer>(nums: impl IntoIterator<Item = impl Borrow<T>>) -> Option<T> {
+    nums.into_iter()
+        .try_fold(T::ONE, |acc, n| lcm(acc, *n.borrow()))
+}
+
+/// Extended Euclidean algorithm: returns `(g, x, y)` such that `a * x + b * y == g == gcd(a, b)`.
+pub fn extended_gcd<T: Signed>(a: T, b: T) -> (T, T, T) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::ONE, T::ZERO);
+    let (mut old_y, mut y) = (T::ZERO, T::ONE);
+
+    while r != T::ZERO {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+
+    if old_r < T::ZERO {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The inverse of `a` modulo `modulus`, in `0..modulus`.
+///
+/// Returns [`None`] if `modulus` is not positive or `a` and `modulus` are not coprime.
+pub fn mod_inverse<T: Signed>(a: T, modulus: T) -> Option<T> {
+    if modulus <= T::ZERO {
+        return None;
+    }
+    let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus);
+    if g != T::ONE {
+        return None;
+    }
+    Some(x.rem_euclid(modulus))
+}
+
+/// Solves a system of congruences `x ≡ residue (mod modulus)` with the Chinese Remainder Theorem.
+///
+/// Moduli need not be pairwise coprime. Returns `(x, lcm of moduli)` with `x` in `0..lcm`,
+/// or [`None`] if the system has no solution, a modulus is not positive or an intermediate overflows.
+pub fn crt<T: Signed>(congruences: impl IntoIterator<Item = (T, T)>) -> Option<(T, T)> {
+    congruences
+        .into_iter()
+        .try_fold((T::ZERO, T::ONE), |(r1, m1), (r2, m2)| {
+            if m2 <= T::ZERO {
+                return None;
+            }
+            let r2 = r2.rem_euclid(m2);
+
+            let (g, p, _) = extended_gcd(m1, m2);
+            let diff = r2.checked_sub(r1)?;
+            if diff % g != T::ZERO {
+                return None;
+            }
+
+            let m2_reduced = m2 / g;
+            let k = (diff / g)
+                .rem_euclid(m2_reduced)
+                .checked_mul(p.rem_euclid(m2_reduced))?
+                .rem_euclid(m2_reduced);
+            let modulus = m1.checked_mul(m2_reduced)?;
+            let x = r1.checked_add(m1.checked_mul(k)?)?.rem_euclid(modulus);
+
+            Some((x, modulus))
+        })
+}
+
+/// Integer square root, rounded down.
+///
+/// # Panics
+/// If `n` is negative.
+pub fn isqrt<T: Integer>(n: T) -> T {
+    assert!(n >= T::ZERO, "square root of negative number {n}");
+    if n < T::TWO {
+        return n;
+    }
+
+    // Newton's method, starting above the root without risking overflow.
+    let mut x = n / T::TWO + T::ONE;
+    loop {
+        let y = (x + n / x) / T::TWO;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd() {
+        assert_eq!(gcd(48u32, 18), 6);
+        assert_eq!(gcd(-48i32, 18), 6);
+        assert_eq!(gcd(0u8, 7), 7);
+        assert_eq!(gcd(0u8, 0), 0);
+    }
+
+    #[test]
+    fn test_lcm() {
+        assert_eq!(lcm(4u64, 6), Some(12));
+        assert_eq!(lcm(-4i64, 6), Some(12));
+        assert_eq!(lcm(0u64, 6), Some(0));
+        assert_eq!(lcm(u64::MAX, u64::MAX - 1), None);
+    }
+
+    #[test]
+    fn test_all_empty() {
+        let empty: [usize; 0] = [];
+        assert_eq!(gcd_all::<usize>(empty), 0);
+        assert_eq!(lcm_all::<usize>(empty), Some(1));
+    }
+
+    #[test]
+    fn test_all_slice_and_iterator() {
+        assert_eq!(gcd_all::<u32>(&[12, 18, 30]), 6);
+        assert_eq!(lcm_all::<usize>(&[2, 3, 4]), Some(12));
+        assert_eq!(lcm_all::<u64>(1..=20u64), Some(232_792_560));
+    }
+
+    #[test]
+    fn test_lcm_all_overflow() {
+        assert_eq!(lcm_all::<u8>(&[16, 15]), Some(240));
+        assert_eq!(lcm_all::<u8>(&[16, 15, 7]), None);
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        let (g, x, y) = extended_gcd(240i64, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, g);
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        assert_eq!(mod_inverse(3i32, 11), Some(4));
+        assert_eq!(mod_inverse(-3i32, 11), Some(7));
+        assert_eq!(mod_inverse(6i32, 9), None);
+        assert_eq!(mod_inverse(6i32, 0), None);
+    }
+
+    #[test]
+    fn test_crt_coprime() {
+        assert_eq!(crt([(2i64, 3), (3, 5), (2, 7)]), Some((23, 105)));
+    }
+
+    #[test]
+    fn test_crt_non_coprime() {
+        assert_eq!(crt([(2i64, 6), (8, 10)]), Some((8, 30)));
+        assert_eq!(crt([(1i64, 6), (2, 4)]), None);
+    }
+
+    #[test]
+    fn test_crt_empty_and_overflow() {
+        let empty: [(i32, i32); 0] = [];
+        assert_eq!(crt(empty), Some((0, 1)));
+        assert_eq!(crt([(0i8, 11), (1, 13)]), None);
+    }
+
+    #[test]
+    fn test_isqrt() {
+        assert_eq!(isqrt(0u32), 0);
+        assert_eq!(isqrt(1u32), 1);
+        assert_eq!(isqrt(15u32), 3);
+        assert_eq!(isqrt(16u32), 4);
+        assert_eq!(isqrt(u64::MAX), u32::MAX as u64);
+        assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+    }
+}
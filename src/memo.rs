@@ -0,0 +1,228 @@
+//! A cache for memoized recursion, with hit/miss statistics.
+//!
+//! Keys can borrow from the input, e.g. `(&[u8], &[usize])`, as long as the input outlives the
+//! cache. The recursive function takes the cache as a parameter and wraps its body in
+//! [`Memo::get_or_compute`]:
+//!
+//! ```
+//! use advent_of_code::memo::Memo;
+//!
+//! fn fib(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+//!     memo.get_or_compute(n, |memo| if n < 2 { n } else { fib(memo, n - 1) + fib(memo, n - 2) })
+//! }
+//!
+//! let mut memo = Memo::new();
+//! assert_eq!(fib(&mut memo, 90), 2880067194370816120);
+//! advent_of_code::verbose!("fib: {}", memo.stats());
+//! ```
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::hash::{BuildHasher, BuildHasherDefault, Hash, Hasher};
+
+/// Cached results by key, using the hasher built by `S`.
+#[derive(Debug, Clone)]
+pub struct Memo<K, V, S = RandomState> {
+    cache: HashMap<K, V, S>,
+    hits: usize,
+    misses: usize,
+}
+
+impl<K: Eq + Hash, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Memo::with_hasher(RandomState::new())
+    }
+}
+
+impl<K: Eq + Hash, V: Clone, S: BuildHasher + Default> Default for Memo<K, V, S> {
+    fn default() -> Self {
+        Memo::with_hasher(S::default())
+    }
+}
+
+impl<K: Eq + Hash, V: Clone, S: BuildHasher> Memo<K, V, S> {
+    /// A cache using another hasher, e.g. [`FxBuildHasher`] for small keys.
+    pub fn with_hasher(hasher: S) -> Self {
+        Memo {
+            cache: HashMap::with_hasher(hasher),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Returns the cached value for `key`, or computes (with access to the cache for recursive
+    /// calls) and caches it.
+    pub fn get_or_compute(&mut self, key: K, compute: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            self.hits += 1;
+            return value.clone();
+        }
+        self.misses += 1;
+        let value = compute(self);
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    /// Empties the cache, keeping the statistics.
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+
+    pub fn stats(&self) -> Stats {
+        Stats {
+            hits: self.hits,
+            misses: self.misses,
+            entries: self.cache.len(),
+        }
+    }
+}
+
+/// How often a [`Memo`] could answer from its cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Stats {
+    pub hits: usize,
+    pub misses: usize,
+    pub entries: usize,
+}
+
+impl Stats {
+    /// Share of lookups answered from the cache, between 0 and 1.
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} hits, {} misses ({:.1}% hit rate), {} entries",
+            self.hits,
+            self.misses,
+            self.hit_rate() * 100.0,
+            self.entries
+        )
+    }
+}
+
+/// The fast, non-cryptographic hash used by the Rust compiler, good for small keys.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FxHasher {
+    hash: u64,
+}
+
+const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+impl FxHasher {
+    fn add(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(SEED);
+    }
+}
+
+impl Hasher for FxHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.add(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        for &byte in chunks.remainder() {
+            self.add(u64::from(byte));
+        }
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.add(u64::from(i));
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.add(u64::from(i));
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.add(i);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.add(i as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+pub type FxBuildHasher = BuildHasherDefault<FxHasher>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ways to write `target` as an ordered sum of `parts`, keyed by borrowed slices.
+    fn compositions<'a>(
+        memo: &mut Memo<(&'a [u32], u32), u64, FxBuildHasher>,
+        parts: &'a [u32],
+        target: u32,
+    ) -> u64 {
+        memo.get_or_compute((parts, target), |memo| {
+            if target == 0 {
+                return 1;
+            }
+            parts
+                .iter()
+                .filter(|&&p| p <= target)
+                .map(|&p| compositions(memo, parts, target - p))
+                .sum()
+        })
+    }
+
+    #[test]
+    fn test_borrowed_keys_and_stats() {
+        let parts = vec![1, 2];
+        let mut memo = Memo::with_hasher(FxBuildHasher::default());
+
+        // Fibonacci: the compositions of 30 into ones and twos.
+        assert_eq!(compositions(&mut memo, &parts, 30), 1_346_269);
+
+        let stats = memo.stats();
+        assert_eq!(stats.misses, 31);
+        assert_eq!(stats.entries, 31);
+        assert_eq!(stats.hits, 29);
+        assert_eq!(
+            stats.to_string(),
+            "29 hits, 31 misses (48.3% hit rate), 31 entries"
+        );
+    }
+
+    #[test]
+    fn test_cached_value_is_reused() {
+        let mut memo: Memo<&str, usize> = Memo::new();
+        let mut calls = 0;
+        for _ in 0..3 {
+            memo.get_or_compute("key", |_| {
+                calls += 1;
+                42
+            });
+        }
+
+        assert_eq!(calls, 1);
+        assert_eq!(memo.get(&"key"), Some(&42));
+        assert_eq!(memo.stats().hits, 2);
+    }
+
+    #[test]
+    fn test_fx_hasher_is_deterministic() {
+        let hash = |value: &(&[u8], &[usize])| FxBuildHasher::default().hash_one(value);
+
+        assert_eq!(hash(&(b"#.?", &[1, 2])), hash(&(b"#.?", &[1, 2])));
+        assert_ne!(hash(&(b"#.?", &[1, 2])), hash(&(b"#.?", &[2, 1])));
+    }
+}
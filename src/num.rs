@@ -0,0 +1,127 @@
+//! Minimal numeric traits so library helpers can be generic over the primitive integer types.
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, Sub, SubAssign};
+
+/// Common interface of the primitive integer types (`u8`..`u128`, `i8`..`i128`, `usize`, `isize`).
+pub trait Integer:
+    Copy
+    + Debug
+    + Display
+    + Default
+    + Ord
+    + Hash
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+    + Sum
+    + Product
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const TWO: Self;
+    const MIN: Self;
+    const MAX: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_div(self, rhs: Self) -> Option<Self>;
+    fn checked_neg(self) -> Option<Self>;
+
+    /// Remainder that is always non-negative, see [`i64::rem_euclid`].
+    fn rem_euclid(self, rhs: Self) -> Self;
+
+    /// Absolute difference, expressed in `Self` (so it may overflow for signed extremes).
+    fn abs_delta(self, other: Self) -> Self {
+        if self > other {
+            self - other
+        } else {
+            other - self
+        }
+    }
+
+    /// Converts from an `i64`, returns [`None`] if the value does not fit.
+    fn from_i64(value: i64) -> Option<Self>;
+
+    /// Converts from a `usize`, returns [`None`] if the value does not fit.
+    fn from_usize(value: usize) -> Option<Self>;
+}
+
+/// Integers that can be negative.
+pub trait Signed: Integer + Neg<Output = Self> {
+    fn abs(self) -> Self;
+    fn signum(self) -> Self;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+                const TWO: Self = 2;
+                const MIN: Self = <$t>::MIN;
+                const MAX: Self = <$t>::MAX;
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, rhs)
+                }
+
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, rhs)
+                }
+
+                fn checked_div(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_div(self, rhs)
+                }
+
+                fn checked_neg(self) -> Option<Self> {
+                    <$t>::checked_neg(self)
+                }
+
+                fn rem_euclid(self, rhs: Self) -> Self {
+                    <$t>::rem_euclid(self, rhs)
+                }
+
+                fn from_i64(value: i64) -> Option<Self> {
+                    value.try_into().ok()
+                }
+
+                fn from_usize(value: usize) -> Option<Self> {
+                    value.try_into().ok()
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_signed {
+    ($($t:ty),*) => {
+        $(
+            impl Signed for $t {
+                fn abs(self) -> Self {
+                    <$t>::abs(self)
+                }
+
+                fn signum(self) -> Self {
+                    <$t>::signum(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+impl_signed!(i8, i16, i32, i64, i128, isize);
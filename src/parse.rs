@@ -0,0 +1,238 @@
+//! Zero-copy helpers for tokenizing puzzle inputs.
+//!
+//! All helpers borrow from the input and report failures as a [`ParseError`] pointing at the
+//! offending slice of that input.
+use std::fmt::Display;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// A literal (a prefix, delimiter or bracket) was expected but not found.
+    Expected(String),
+    /// An integer token could not be parsed.
+    InvalidInt(ParseIntError),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError<'a> {
+    pub kind: ErrorKind,
+    /// The slice of the input where the problem was found.
+    pub at: &'a str,
+}
+
+impl<'a> ParseError<'a> {
+    pub fn new(kind: ErrorKind, at: &'a str) -> Self {
+        ParseError { kind, at }
+    }
+
+    fn expected(literal: impl ToString, at: &'a str) -> Self {
+        ParseError::new(ErrorKind::Expected(literal.to_string()), at)
+    }
+}
+
+impl Display for ParseError<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            ErrorKind::Expected(literal) => write!(f, "expected `{literal}`"),
+            ErrorKind::InvalidInt(e) => write!(f, "invalid integer `{}`: {e}", self.at),
+        }
+    }
+}
+
+impl std::error::Error for ParseError<'_> {}
+
+pub type Result<'a, T> = std::result::Result<T, ParseError<'a>>;
+
+/// Parses a single (surrounding whitespace trimmed) integer.
+pub fn int<T: FromStr<Err = ParseIntError>>(s: &str) -> Result<'_, T> {
+    let token = s.trim();
+    token
+        .parse()
+        .map_err(|e| ParseError::new(ErrorKind::InvalidInt(e), token))
+}
+
+/// Iterates the runs of digits in `s`, including a directly preceding `-` when `signed`.
+pub fn int_tokens(s: &str, signed: bool) -> impl Iterator<Item = &str> {
+    let bytes = s.as_bytes();
+    let mut pos = 0;
+    std::iter::from_fn(move || {
+        while pos < bytes.len() {
+            let start = pos;
+            let negative =
+                signed && bytes[pos] == b'-' && bytes.get(pos + 1).is_some_and(u8::is_ascii_digit);
+            if negative || bytes[pos].is_ascii_digit() {
+                pos += 1;
+                while pos < bytes.len() && bytes[pos].is_ascii_digit() {
+                    pos += 1;
+                }
+                return Some(&s[start..pos]);
+            }
+            pos += 1;
+        }
+        None
+    })
+}
+
+/// All unsigned integers in `s`, ignoring anything in between (a `-` is treated as a separator).
+pub fn uints<T: FromStr<Err = ParseIntError>>(s: &str) -> Result<'_, Vec<T>> {
+    int_tokens(s, false).map(int).collect()
+}
+
+/// All (possibly negative) integers in `s`, ignoring anything in between.
+pub fn ints<T: FromStr<Err = ParseIntError>>(s: &str) -> Result<'_, Vec<T>> {
+    int_tokens(s, true).map(int).collect()
+}
+
+/// Splits `s` into sections separated by one or more blank lines.
+pub fn sections(s: &str) -> impl Iterator<Item = &str> {
+    let mut pos = 0;
+    std::iter::from_fn(move || {
+        let mut start = None;
+        let mut end = pos;
+        while pos < s.len() {
+            let line_end = s[pos..].find('\n').map_or(s.len(), |i| pos + i);
+            let blank = s[pos..line_end].trim().is_empty();
+            let next = (line_end + 1).min(s.len());
+            match (blank, start) {
+                (true, Some(_)) => {
+                    pos = next;
+                    break;
+                }
+                (true, None) => {}
+                (false, None) => {
+                    start = Some(pos);
+                    end = line_end;
+                }
+                (false, Some(_)) => end = line_end,
+            }
+            pos = next;
+        }
+        start.map(|start| s[start..end].trim_end_matches('\r'))
+    })
+}
+
+/// Expects `s` to start with `literal`, returns the rest.
+pub fn literal<'a>(s: &'a str, literal: &str) -> Result<'a, &'a str> {
+    s.strip_prefix(literal)
+        .ok_or_else(|| ParseError::expected(literal, s))
+}
+
+/// Parses a numbered label such as `Game 4:` or `Card  12:`, returns the number and the rest.
+pub fn numbered<'a, T: FromStr<Err = ParseIntError>>(
+    s: &'a str,
+    keyword: &str,
+) -> Result<'a, (T, &'a str)> {
+    let rest = literal(s, keyword)?;
+    let (number, rest) = split_once(rest, ':')?;
+    Ok((int(number)?, rest))
+}
+
+/// Like [`str::split_once`], but failing with an error pointing at `s`.
+pub fn split_once(s: &str, delimiter: char) -> Result<'_, (&str, &str)> {
+    s.split_once(delimiter)
+        .ok_or_else(|| ParseError::expected(delimiter, s))
+}
+
+/// Parses a `key=value` pair (for any `separator`), with both sides trimmed.
+pub fn key_value(s: &str, separator: char) -> Result<'_, (&str, &str)> {
+    let (key, value) = split_once(s.trim(), separator)?;
+    Ok((key.trim(), value.trim()))
+}
+
+/// Parses a list of `key=value` pairs, e.g. `x=787,m=2655`.
+pub fn key_values(s: &str, pair_separator: char, separator: char) -> Result<'_, Vec<(&str, &str)>> {
+    s.split(pair_separator)
+        .map(|pair| key_value(pair, separator))
+        .collect()
+}
+
+/// Parses a `name{inner}` record, returns the (possibly empty) name and the inner text.
+pub fn braced(s: &str) -> Result<'_, (&str, &str)> {
+    let s = s.trim();
+    let (name, rest) = split_once(s, '{')?;
+    let inner = rest
+        .strip_suffix('}')
+        .ok_or_else(|| ParseError::expected('}', &s[s.len()..]))?;
+    Ok((name, inner))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uints() {
+        assert_eq!(uints::<u32>("Time:      7  15   30"), Ok(vec![7, 15, 30]));
+        assert_eq!(uints::<u8>("1-3 a: abc"), Ok(vec![1, 3]));
+        assert_eq!(uints::<u8>(""), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_ints() {
+        assert_eq!(
+            ints::<i64>("19, 13, 30 @ -2,  1, -2"),
+            Ok(vec![19, 13, 30, -2, 1, -2])
+        );
+        assert_eq!(ints::<i32>("a-b -"), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_int_overflow() {
+        let input = "12 300 4";
+        let result = uints::<u8>(input).unwrap_err();
+
+        assert!(matches!(result.kind, ErrorKind::InvalidInt(_)));
+        assert_eq!(result.at, "300");
+        assert_eq!(result.at.as_ptr(), input[3..].as_ptr());
+    }
+
+    #[test]
+    fn test_sections() {
+        let input = "a\nb\n\n\nc\n  \nd\n";
+        let result: Vec<_> = sections(input).collect();
+        assert_eq!(result, vec!["a\nb", "c", "d"]);
+
+        assert_eq!(sections("\n\n").count(), 0);
+        assert_eq!(sections("x\r\n\r\ny\r\n").collect::<Vec<_>>(), ["x", "y"]);
+    }
+
+    #[test]
+    fn test_literal() {
+        assert_eq!(literal("Time: 7", "Time:"), Ok(" 7"));
+        assert_eq!(
+            literal("Distance: 9", "Time:"),
+            Err(ParseError::expected("Time:", "Distance: 9"))
+        );
+    }
+
+    #[test]
+    fn test_numbered() {
+        assert_eq!(
+            numbered::<u32>("Game 4: 1 green", "Game"),
+            Ok((4, " 1 green"))
+        );
+        assert_eq!(numbered::<u16>("Card  12: 41", "Card"), Ok((12, " 41")));
+        assert!(numbered::<u16>("Card x: 41", "Card").is_err());
+    }
+
+    #[test]
+    fn test_key_values() {
+        assert_eq!(key_value(" x = 787 ", '='), Ok(("x", "787")));
+        assert_eq!(
+            key_values("x=787,m=2655", ',', '='),
+            Ok(vec![("x", "787"), ("m", "2655")])
+        );
+        assert_eq!(key_values("x=787,m", ',', '=').unwrap_err().at, "m");
+    }
+
+    #[test]
+    fn test_braced() {
+        assert_eq!(braced("px{a<2006:qkq,rfg}"), Ok(("px", "a<2006:qkq,rfg")));
+        assert_eq!(braced("{x=787}"), Ok(("", "x=787")));
+        assert_eq!(
+            braced("px{a<2006").unwrap_err().kind,
+            ErrorKind::Expected("}".to_string())
+        );
+    }
+}
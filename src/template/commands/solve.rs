@@ -2,7 +2,14 @@ use std::process::{Command, Stdio};
 
 use crate::Day;
 
-pub fn handle(day: Day, release: bool, time: bool, submit_part: Option<u8>) {
+pub fn handle(
+    day: Day,
+    release: bool,
+    time: bool,
+    verbose: bool,
+    params: &[String],
+    submit_part: Option<u8>,
+) {
     let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
 
     if release {
@@ -20,6 +27,15 @@ pub fn handle(day: Day, release: bool, time: bool, submit_part: Option<u8>) {
         cmd_args.push("--time".to_string());
     }
 
+    if verbose {
+        cmd_args.push("--verbose".to_string());
+    }
+
+    for param in params {
+        cmd_args.push("--param".to_string());
+        cmd_args.push(param.clone());
+    }
+
     let mut cmd = Command::new("cargo")
         .args(&cmd_args)
         .stdout(Stdio::inherit())
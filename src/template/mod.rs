@@ -46,3 +46,15 @@ macro_rules! solution {
         }
     };
 }
+
+/// Prints diagnostics (to stderr) only when the solution is run with `--verbose`.
+///
+/// Example: `advent_of_code::verbose!("cycle starts at {prefix}")`
+#[macro_export]
+macro_rules! verbose {
+    ($($arg:tt)*) => {
+        if $crate::template::runner::is_verbose() {
+            eprintln!($($arg)*);
+        }
+    };
+}
@@ -1,27 +1,69 @@
 /// Encapsulates code that interacts with solution functions.
+use crate::error::InputError;
 use crate::template::{aoc_cli, ANSI_ITALIC, ANSI_RESET};
 use crate::Day;
+use std::cell::Cell;
 use std::fmt::Display;
 use std::io::{stdout, Write};
 use std::process::Output;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
 use std::time::{Duration, Instant};
 use std::{cmp, env, process};
 
 use super::ANSI_BOLD;
 
-pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
+/// What a solution part can return: an optional answer, or an answer or an error in the input.
+pub trait Answer {
+    type Output: Display;
+
+    fn into_answer(self) -> Result<Option<Self::Output>, InputError>;
+}
+
+impl<T: Display> Answer for Option<T> {
+    type Output = T;
+
+    fn into_answer(self) -> Result<Option<T>, InputError> {
+        Ok(self)
+    }
+}
+
+impl<T: Display> Answer for Result<T, InputError> {
+    type Output = T;
+
+    fn into_answer(self) -> Result<Option<T>, InputError> {
+        self.map(Some)
+    }
+}
+
+pub fn run_part<I: Clone, A: Answer>(func: impl Fn(I) -> A, input: I, day: Day, part: u8) {
     let part_str = format!("Part {part}");
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    let (result, duration, samples) = run_timed(
+        |input| func(input).into_answer(),
+        input,
+        |result| print_result(answer_of(result), &part_str, ""),
+    );
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    print_result(
+        answer_of(&result),
+        &part_str,
+        &format_duration(&duration, samples),
+    );
 
-    if let Some(result) = result {
-        submit_result(result, day, part);
+    match result {
+        Ok(Some(result)) => {
+            submit_result(result, day, part);
+        }
+        Ok(None) => {}
+        Err(error) => eprintln!("{error}"),
     }
 }
 
+fn answer_of<T>(result: &Result<Option<T>, InputError>) -> Option<&T> {
+    result.as_ref().ok().and_then(Option::as_ref)
+}
+
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
 ///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
@@ -45,22 +87,76 @@ fn run_timed<I: Clone, T>(
     (result, run.0, run.1)
 }
 
+static BENCHING: AtomicBool = AtomicBool::new(false);
+
+/// Whether the solution was run with `--verbose`, see [`crate::verbose!`].
+///
+/// Always `false` while benching, so repeated runs do not flood the output.
+pub fn is_verbose() -> bool {
+    static VERBOSE: OnceLock<bool> = OnceLock::new();
+    *VERBOSE.get_or_init(|| env::args().any(|x| x == "--verbose"))
+        && !BENCHING.load(Ordering::Relaxed)
+}
+
+thread_local! {
+    /// Parameters set with [`with_params`], which take precedence over the command line.
+    static OVERRIDES: Cell<&'static [(&'static str, &'static str)]> = const { Cell::new(&[]) };
+}
+
+/// The value the solution was run with as `--param <name>=<value>`, if any.
+///
+/// Lets solutions take puzzle parameters from the command line, e.g. `--param bag="1 red"`.
+pub fn param(name: &str) -> Option<&'static str> {
+    let overridden = OVERRIDES.with(|overrides| {
+        overrides
+            .get()
+            .iter()
+            .find(|&&(key, _)| key == name)
+            .map(|&(_, value)| value)
+    });
+    if overridden.is_some() {
+        return overridden;
+    }
+
+    static PARAMS: OnceLock<Vec<(String, String)>> = OnceLock::new();
+    PARAMS
+        .get_or_init(|| parse_params(&env::args().collect::<Vec<_>>()))
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+/// Runs `f` as if the solution had been run with `--param <name>=<value>` for each pair.
+///
+/// Only affects the current thread, so tests can run a solution with the example's parameters.
+pub fn with_params<T>(params: &'static [(&'static str, &'static str)], f: impl FnOnce() -> T) -> T {
+    let previous = OVERRIDES.replace(params);
+    let result = f();
+    OVERRIDES.set(previous);
+    result
+}
+
+/// The `(name, value)` pairs of every `--param <name>=<value>` in `args`.
+fn parse_params(args: &[String]) -> Vec<(String, String)> {
+    args.windows(2)
+        .filter(|pair| pair[0] == "--param")
+        .filter_map(|pair| pair[1].split_once('='))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
 fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
     let mut stdout = stdout();
 
     print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
     let _ = stdout.flush();
 
-    let bench_iterations = cmp::min(
-        10000,
-        cmp::max(
-            Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10),
-            10,
-        ),
-    );
+    let bench_iterations =
+        (Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10)).clamp(10, 10000);
 
     let mut timers: Vec<Duration> = vec![];
 
+    BENCHING.store(true, Ordering::Relaxed);
     for _ in 0..bench_iterations {
         // need a clone here to make the borrow checker happy.
         let cloned = input.clone();
@@ -68,6 +164,7 @@ fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) ->
         func(cloned);
         timers.push(timer.elapsed());
     }
+    BENCHING.store(false, Ordering::Relaxed);
 
     (
         #[allow(clippy::cast_possible_truncation)]
@@ -92,7 +189,7 @@ fn format_duration(duration: &Duration, samples: u128) -> String {
     }
 }
 
-fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
+fn print_result<T: Display>(result: Option<&T>, part: &str, duration_str: &str) {
     let is_intermediate_result = duration_str.is_empty();
 
     match result {
@@ -165,3 +262,43 @@ fn submit_result<T: Display>(
     println!("Submitting result via aoc-cli...");
     Some(aoc_cli::submit(day, part, &result.to_string()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{param, parse_params, with_params};
+
+    #[test]
+    fn test_parse_params() {
+        let args: Vec<String> = [
+            "target/debug/02",
+            "--param",
+            "bag=1 red, 2 blue",
+            "--verbose",
+            "--param",
+            "equation=a=b",
+            "--param",
+            "no-value",
+            "--param",
+        ]
+        .map(String::from)
+        .into();
+
+        assert_eq!(
+            parse_params(&args),
+            vec![
+                ("bag".to_string(), "1 red, 2 blue".to_string()),
+                ("equation".to_string(), "a=b".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_with_params() {
+        assert_eq!(param("area"), None);
+
+        let area = with_params(&[("area", "7..=27")], || param("area"));
+        assert_eq!(area, Some("7..=27"));
+
+        assert_eq!(param("area"), None);
+    }
+}